@@ -1,7 +1,32 @@
 extern crate pkg_config;
 
-fn main() {
-    pkg_config::probe_library("libimobiledevice-1.0").unwrap();
+use std::env;
+
+/// Whether a pkg-config version string like "1.3.0" is at least 1.3.
+fn is_1_3_or_later(version: &str) -> bool {
+    let mut parts = version.split('.').map(|part| {
+        let digits = part.find(|c: char| !c.is_ascii_digit()).unwrap_or(part.len());
+        part[..digits].parse::<u32>().unwrap_or(0)
+    });
+    let major = parts.next().unwrap_or(0);
+    let minor = parts.next().unwrap_or(0);
+    (major, minor) >= (1, 3)
 }
 
+fn main() {
+    let mut config = pkg_config::Config::new();
+    if env::var_os("CARGO_FEATURE_LIBIMOBILEDEVICE_1_3").is_some() {
+        config.atleast_version("1.3.0");
+    }
+    let library = config.probe("libimobiledevice-1.0").unwrap();
 
+    // Some error codes were renumbered in 1.3, so they must follow the library actually linked,
+    // not the feature. Dependents read the result as `DEP_IMOBILEDEVICE_ABI`.
+    println!("cargo:rustc-check-cfg=cfg(libimobiledevice_1_3)");
+    if is_1_3_or_later(&library.version) {
+        println!("cargo:rustc-cfg=libimobiledevice_1_3");
+        println!("cargo:abi=1.3");
+    } else {
+        println!("cargo:abi=1.2");
+    }
+}
@@ -8,70 +8,40 @@ use std::os::raw::{c_char, c_void, c_int};
 pub const AFC_SERVICE_NAME: &'static [u8] = b"com.apple.afc\0";
 pub const AFC2_SERVICE_NAME: &'static [u8] = b"com.apple.afc2\0";
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-#[repr(i32)]
-pub enum afc_error_t {
-    Success = 0,
-    UnknownError = 1,
-    OpHeaderInvalid = 2,
-    NoResources = 3,
-    ReadError = 4,
-    WriteError = 5,
-    UnknownPacketType = 6,
-    InvalidArg = 7,
-    ObjectNotFound = 8,
-    ObjectIsDir = 9,
-    PermDenied = 10,
-    ServiceNotConnected = 11,
-    OpTimeout = 12,
-    TooMuchData = 13,
-    EndOfData = 14,
-    OpNotSupported = 15,
-    ObjectExists = 16,
-    ObjectBusy = 17,
-    NoSpaceLeft = 18,
-    OpWouldBlock = 19,
-    IoError = 20,
-    OpInterrupted = 21,
-    OpInProgress = 22,
-    InternalError = 23,
-    MuxError = 30,
-    NoMem = 31,
-    NotEnoughData = 32,
-    DirNotEmpty = 33,
-    ForceSignedType = -1,
+error_code! {
+    afc_error_t {
+        Success = 0 => AFC_E_SUCCESS,
+        UnknownError = 1 => AFC_E_UNKNOWN_ERROR,
+        OpHeaderInvalid = 2 => AFC_E_OP_HEADER_INVALID,
+        NoResources = 3 => AFC_E_NO_RESOURCES,
+        ReadError = 4 => AFC_E_READ_ERROR,
+        WriteError = 5 => AFC_E_WRITE_ERROR,
+        UnknownPacketType = 6 => AFC_E_UNKNOWN_PACKET_TYPE,
+        InvalidArg = 7 => AFC_E_INVALID_ARG,
+        ObjectNotFound = 8 => AFC_E_OBJECT_NOT_FOUND,
+        ObjectIsDir = 9 => AFC_E_OBJECT_IS_DIR,
+        PermDenied = 10 => AFC_E_PERM_DENIED,
+        ServiceNotConnected = 11 => AFC_E_SERVICE_NOT_CONNECTED,
+        OpTimeout = 12 => AFC_E_OP_TIMEOUT,
+        TooMuchData = 13 => AFC_E_TOO_MUCH_DATA,
+        EndOfData = 14 => AFC_E_END_OF_DATA,
+        OpNotSupported = 15 => AFC_E_OP_NOT_SUPPORTED,
+        ObjectExists = 16 => AFC_E_OBJECT_EXISTS,
+        ObjectBusy = 17 => AFC_E_OBJECT_BUSY,
+        NoSpaceLeft = 18 => AFC_E_NO_SPACE_LEFT,
+        OpWouldBlock = 19 => AFC_E_OP_WOULD_BLOCK,
+        IoError = 20 => AFC_E_IO_ERROR,
+        OpInterrupted = 21 => AFC_E_OP_INTERRUPTED,
+        OpInProgress = 22 => AFC_E_OP_IN_PROGRESS,
+        InternalError = 23 => AFC_E_INTERNAL_ERROR,
+        MuxError = 30 => AFC_E_MUX_ERROR,
+        NoMem = 31 => AFC_E_NO_MEM,
+        NotEnoughData = 32 => AFC_E_NOT_ENOUGH_DATA,
+        DirNotEmpty = 33 => AFC_E_DIR_NOT_EMPTY,
+        ForceSignedType = -1 => AFC_E_FORCE_SIGNED_TYPE,
+    }
 }
 
-pub const AFC_E_SUCCESS: afc_error_t = afc_error_t::Success;
-pub const AFC_E_UNKNOWN_ERROR: afc_error_t = afc_error_t::UnknownError;
-pub const AFC_E_OP_HEADER_INVALID: afc_error_t = afc_error_t::OpHeaderInvalid;
-pub const AFC_E_NO_RESOURCES: afc_error_t = afc_error_t::NoResources;
-pub const AFC_E_READ_ERROR: afc_error_t = afc_error_t::ReadError;
-pub const AFC_E_WRITE_ERROR: afc_error_t = afc_error_t::WriteError;
-pub const AFC_E_UNKNOWN_PACKET_TYPE: afc_error_t = afc_error_t::UnknownPacketType;
-pub const AFC_E_INVALID_ARG: afc_error_t = afc_error_t::InvalidArg;
-pub const AFC_E_OBJECT_NOT_FOUND: afc_error_t = afc_error_t::ObjectNotFound;
-pub const AFC_E_OBJECT_IS_DIR: afc_error_t = afc_error_t::ObjectIsDir;
-pub const AFC_E_PERM_DENIED: afc_error_t = afc_error_t::PermDenied;
-pub const AFC_E_SERVICE_NOT_CONNECTED: afc_error_t = afc_error_t::ServiceNotConnected;
-pub const AFC_E_OP_TIMEOUT: afc_error_t = afc_error_t::OpTimeout;
-pub const AFC_E_TOO_MUCH_DATA: afc_error_t = afc_error_t::TooMuchData;
-pub const AFC_E_END_OF_DATA: afc_error_t = afc_error_t::EndOfData;
-pub const AFC_E_OP_NOT_SUPPORTED: afc_error_t = afc_error_t::OpNotSupported;
-pub const AFC_E_OBJECT_EXISTS: afc_error_t = afc_error_t::ObjectExists;
-pub const AFC_E_OBJECT_BUSY: afc_error_t = afc_error_t::ObjectBusy;
-pub const AFC_E_NO_SPACE_LEFT: afc_error_t = afc_error_t::NoSpaceLeft;
-pub const AFC_E_OP_WOULD_BLOCK: afc_error_t = afc_error_t::OpWouldBlock;
-pub const AFC_E_IO_ERROR: afc_error_t = afc_error_t::IoError;
-pub const AFC_E_OP_INTERRUPTED: afc_error_t = afc_error_t::OpInterrupted;
-pub const AFC_E_OP_IN_PROGRESS: afc_error_t = afc_error_t::OpInProgress;
-pub const AFC_E_INTERNAL_ERROR: afc_error_t = afc_error_t::InternalError;
-pub const AFC_E_MUX_ERROR: afc_error_t = afc_error_t::MuxError;
-pub const AFC_E_NO_MEM: afc_error_t = afc_error_t::NoMem;
-pub const AFC_E_NOT_ENOUGH_DATA: afc_error_t = afc_error_t::NotEnoughData;
-pub const AFC_E_DIR_NOT_EMPTY: afc_error_t = afc_error_t::DirNotEmpty;
-pub const AFC_E_FORCE_SIGNED_TYPE: afc_error_t = afc_error_t::ForceSignedType;
-
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u32)]
 pub enum afc_file_mode_t {
@@ -147,25 +117,3 @@ extern "C" {
     pub fn afc_get_device_info_key(client: afc_client_t, key: *const c_char, value: *mut *mut c_char) -> afc_error_t;
 }
 
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
@@ -0,0 +1,53 @@
+//! Bindings to `bt_packet_logger.h`, for capturing Bluetooth HCI traffic.
+//!
+//! This service is newer than libimobiledevice 1.3.0, and needs a libimobiledevice built from a
+//! later source tree even though the feature only checks for 1.3.0.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+
+use std::os::raw::{c_char, c_uint, c_void};
+
+pub const BT_PACKETLOGGER_SERVICE_NAME: &'static [u8] = b"com.apple.bluetooth.BTPacketLogger\0";
+
+pub const BT_MAX_PACKET_SIZE: usize = 65535;
+
+error_code! {
+    bt_packet_logger_error_t {
+        Success = 0 => BT_PACKET_LOGGER_E_SUCCESS,
+        InvalidArg = -1 => BT_PACKET_LOGGER_E_INVALID_ARG,
+        MuxError = -2 => BT_PACKET_LOGGER_E_MUX_ERROR,
+        SslError = -3 => BT_PACKET_LOGGER_E_SSL_ERROR,
+        NotEnoughData = -4 => BT_PACKET_LOGGER_E_NOT_ENOUGH_DATA,
+        Timeout = -5 => BT_PACKET_LOGGER_E_TIMEOUT,
+        UnknownError = -256 => BT_PACKET_LOGGER_E_UNKNOWN_ERROR,
+    }
+}
+
+/// The header preceding each captured packet. All fields are big-endian.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct bt_packet_logger_header {
+    pub length: u32,
+    pub ts_secs: u32,
+    pub ts_usecs: u32,
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct bt_packet_logger_client_private(c_void);
+pub type bt_packet_logger_client_t = *mut bt_packet_logger_client_private;
+
+/// Receives each captured packet, starting with a `bt_packet_logger_header`, from a background
+/// thread.
+pub type bt_packet_logger_receive_cb_t = unsafe extern "C" fn(data: *mut u8, len: u16, user_data: *mut c_void);
+
+extern "C" {
+    pub fn bt_packet_logger_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut bt_packet_logger_client_t) -> bt_packet_logger_error_t;
+    pub fn bt_packet_logger_client_start_service(device: idevice_t, client: *mut bt_packet_logger_client_t, label: *const c_char) -> bt_packet_logger_error_t;
+    pub fn bt_packet_logger_client_free(client: bt_packet_logger_client_t) -> bt_packet_logger_error_t;
+
+    pub fn bt_packet_logger_start_capture(client: bt_packet_logger_client_t, callback: bt_packet_logger_receive_cb_t, user_data: *mut c_void) -> bt_packet_logger_error_t;
+    pub fn bt_packet_logger_stop_capture(client: bt_packet_logger_client_t) -> bt_packet_logger_error_t;
+    pub fn bt_packet_logger_receive_with_timeout(client: bt_packet_logger_client_t, data: *mut c_char, size: u32, received: *mut u32, timeout: c_uint) -> bt_packet_logger_error_t;
+}
@@ -0,0 +1,73 @@
+//! Runtime detection of functions added in newer library versions.
+//!
+//! The symbols are looked up in the running process with `dlsym`, so a binary can check what the
+//! installed libplist, libusbmuxd and libimobiledevice provide before calling into optional
+//! functionality. Only available on Unix with the `runtime-detect` feature.
+//!
+//! This does not make the functions declared in this crate optional: those enabled by the
+//! `libimobiledevice-1-3` feature are still linked normally, and a binary using them fails to load
+//! against an older library. To degrade gracefully, call a newer function through the pointer
+//! returned by [`resolve`](fn.resolve.html) instead.
+
+use libloading::os::unix::Library;
+
+use std::os::raw::c_void;
+
+/// Checks whether the symbol `name` (NUL-terminated) is loaded into the current process.
+pub fn has_symbol(name: &[u8]) -> bool {
+    let this = Library::this();
+    unsafe { this.get::<*mut c_void>(name).is_ok() }
+}
+
+/// Looks up the symbol `name` (NUL-terminated) in the current process, returning it as `T`,
+/// typically an `unsafe extern "C" fn` pointer.
+///
+/// This is unsafe because `T` must match the actual type of the symbol.
+pub unsafe fn resolve<T: Copy>(name: &[u8]) -> Option<T> {
+    Library::this().get::<T>(name).ok().map(|symbol| *symbol)
+}
+
+/// Availability of optional functions in the installed libraries.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Capabilities {
+    /// `plist_to_json` from libplist 2.3.
+    pub plist_to_json: bool,
+    /// `usbmuxd_events_subscribe` from libusbmuxd 2.0.
+    pub usbmuxd_events_subscribe: bool,
+    /// `idevice_get_device_list_extended` from libimobiledevice 1.3.
+    pub device_list_extended: bool,
+    /// `idevice_new_with_options` from libimobiledevice 1.3.
+    pub new_with_options: bool,
+    /// `idevice_connection_get_fd` from libimobiledevice 1.2.
+    pub connection_get_fd: bool,
+    /// `idevice_events_subscribe` (context-based subscription) from libimobiledevice 1.3.
+    pub events_subscribe: bool,
+}
+
+impl Capabilities {
+    /// Probes the current process for every optional function.
+    pub fn detect() -> Capabilities {
+        Capabilities {
+            plist_to_json: has_symbol(b"plist_to_json\0"),
+            usbmuxd_events_subscribe: has_symbol(b"usbmuxd_events_subscribe\0"),
+            device_list_extended: has_symbol(b"idevice_get_device_list_extended\0"),
+            new_with_options: has_symbol(b"idevice_new_with_options\0"),
+            connection_get_fd: has_symbol(b"idevice_connection_get_fd\0"),
+            events_subscribe: has_symbol(b"idevice_events_subscribe\0"),
+        }
+    }
+}
+
+#[test]
+fn test_detect_baseline_symbols() {
+    assert!(has_symbol(b"idevice_new\0"));
+    assert!(!has_symbol(b"idevice_no_such_function\0"));
+}
+
+#[test]
+fn test_resolve() {
+    use idevice::{idevice_t, idevice_error_t};
+    type IdeviceFree = unsafe extern "C" fn(device: idevice_t) -> idevice_error_t;
+    assert!(unsafe { resolve::<IdeviceFree>(b"idevice_free\0") }.is_some());
+    assert!(unsafe { resolve::<IdeviceFree>(b"idevice_no_such_function\0") }.is_none());
+}
@@ -0,0 +1,53 @@
+//! Bindings to `companion_proxy.h`, for Apple Watches paired with a device.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_char, c_void};
+
+pub const COMPANION_PROXY_SERVICE_NAME: &'static [u8] = b"com.apple.companion_proxy\0";
+
+error_code! {
+    companion_proxy_error_t {
+        Success = 0 => COMPANION_PROXY_E_SUCCESS,
+        InvalidArg = -1 => COMPANION_PROXY_E_INVALID_ARG,
+        PlistError = -2 => COMPANION_PROXY_E_PLIST_ERROR,
+        MuxError = -3 => COMPANION_PROXY_E_MUX_ERROR,
+        SslError = -4 => COMPANION_PROXY_E_SSL_ERROR,
+        NotEnoughData = -5 => COMPANION_PROXY_E_NOT_ENOUGH_DATA,
+        Timeout = -6 => COMPANION_PROXY_E_TIMEOUT,
+        OpInProgress = -7 => COMPANION_PROXY_E_OP_IN_PROGRESS,
+        NoDevices = -100 => COMPANION_PROXY_E_NO_DEVICES,
+        UnsupportedKey = -101 => COMPANION_PROXY_E_UNSUPPORTED_KEY,
+        TimeoutReply = -102 => COMPANION_PROXY_E_TIMEOUT_REPLY,
+        UnknownError = -256 => COMPANION_PROXY_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct companion_proxy_client_private(c_void);
+pub type companion_proxy_client_t = *mut companion_proxy_client_private;
+
+/// Receives companion devices being added or removed, from a background thread.
+pub type companion_proxy_device_event_cb_t = unsafe extern "C" fn(event: plist_t, user_data: *mut c_void);
+
+extern "C" {
+    pub fn companion_proxy_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut companion_proxy_client_t) -> companion_proxy_error_t;
+    pub fn companion_proxy_client_start_service(device: idevice_t, client: *mut companion_proxy_client_t, label: *const c_char) -> companion_proxy_error_t;
+    pub fn companion_proxy_client_free(client: companion_proxy_client_t) -> companion_proxy_error_t;
+
+    pub fn companion_proxy_send(client: companion_proxy_client_t, plist: plist_t) -> companion_proxy_error_t;
+    pub fn companion_proxy_receive(client: companion_proxy_client_t, plist: *mut plist_t) -> companion_proxy_error_t;
+
+    /// Gets the UDIDs of the paired companion devices as an array.
+    pub fn companion_proxy_get_device_registry(client: companion_proxy_client_t, paired_devices: *mut plist_t) -> companion_proxy_error_t;
+    pub fn companion_proxy_start_listening_for_devices(client: companion_proxy_client_t, callback: companion_proxy_device_event_cb_t, user_data: *mut c_void) -> companion_proxy_error_t;
+    pub fn companion_proxy_stop_listening_for_devices(client: companion_proxy_client_t) -> companion_proxy_error_t;
+    pub fn companion_proxy_get_value_from_registry(client: companion_proxy_client_t, companion_udid: *const c_char, key: *const c_char, value: *mut plist_t) -> companion_proxy_error_t;
+
+    /// Forwards a port of the companion device to a port of the device, stored in `forward_port`.
+    pub fn companion_proxy_start_forwarding_service_port(client: companion_proxy_client_t, remote_port: u16, service_name: *const c_char, forward_port: *mut u16, options: plist_t) -> companion_proxy_error_t;
+    pub fn companion_proxy_stop_forwarding_service_port(client: companion_proxy_client_t, remote_port: u16) -> companion_proxy_error_t;
+}
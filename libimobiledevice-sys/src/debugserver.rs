@@ -0,0 +1,67 @@
+//! Bindings to `debugserver.h`.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+
+pub const DEBUGSERVER_SERVICE_NAME: &'static [u8] = b"com.apple.debugserver\0";
+
+error_code! {
+    debugserver_error_t {
+        Success = 0 => DEBUGSERVER_E_SUCCESS,
+        InvalidArg = -1 => DEBUGSERVER_E_INVALID_ARG,
+        MuxError = -2 => DEBUGSERVER_E_MUX_ERROR,
+        SslError = -3 => DEBUGSERVER_E_SSL_ERROR,
+        ResponseError = -4 => DEBUGSERVER_E_RESPONSE_ERROR,
+        Timeout = -5 => DEBUGSERVER_E_TIMEOUT,
+        UnknownError = -256 => DEBUGSERVER_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct debugserver_client_private(c_void);
+pub type debugserver_client_t = *mut debugserver_client_private;
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct debugserver_command_private(c_void);
+pub type debugserver_command_t = *mut debugserver_command_private;
+
+extern "C" {
+    pub fn debugserver_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut debugserver_client_t) -> debugserver_error_t;
+    pub fn debugserver_client_start_service(device: idevice_t, client: *mut debugserver_client_t, label: *const c_char) -> debugserver_error_t;
+    pub fn debugserver_client_free(client: debugserver_client_t) -> debugserver_error_t;
+
+    pub fn debugserver_client_send(client: debugserver_client_t, data: *const c_char, size: u32, sent: *mut u32) -> debugserver_error_t;
+    pub fn debugserver_client_receive_with_timeout(client: debugserver_client_t, data: *mut c_char, size: u32, received: *mut u32, timeout: c_uint) -> debugserver_error_t;
+    pub fn debugserver_client_receive(client: debugserver_client_t, data: *mut c_char, size: u32, received: *mut u32) -> debugserver_error_t;
+
+    /// Sends a GDB remote protocol command and receives the response, to be released with
+    /// `free()`.
+    #[cfg(not(libimobiledevice_1_3))]
+    pub fn debugserver_client_send_command(client: debugserver_client_t, command: debugserver_command_t, response: *mut *mut c_char) -> debugserver_error_t;
+    /// Sends a GDB remote protocol command and receives the response, to be released with
+    /// `free()`. The response may contain NUL bytes, hence `response_size`, which may be NULL.
+    #[cfg(libimobiledevice_1_3)]
+    pub fn debugserver_client_send_command(client: debugserver_client_t, command: debugserver_command_t, response: *mut *mut c_char, response_size: *mut usize) -> debugserver_error_t;
+    #[cfg(not(libimobiledevice_1_3))]
+    pub fn debugserver_client_receive_response(client: debugserver_client_t, response: *mut *mut c_char) -> debugserver_error_t;
+    #[cfg(libimobiledevice_1_3)]
+    pub fn debugserver_client_receive_response(client: debugserver_client_t, response: *mut *mut c_char, response_size: *mut usize) -> debugserver_error_t;
+    #[cfg(feature="libimobiledevice-1-3")]
+    pub fn debugserver_client_set_ack_mode(client: debugserver_client_t, enabled: c_int) -> debugserver_error_t;
+
+    /// Sets the program and its arguments to launch, with `argv[0]` being the app's executable
+    /// path on the device.
+    pub fn debugserver_client_set_argv(client: debugserver_client_t, argc: c_int, argv: *mut *mut c_char, response: *mut *mut c_char) -> debugserver_error_t;
+    /// Adds an environment variable for the launched program, given as `"NAME=value"`.
+    pub fn debugserver_client_set_environment_hex_encoded(client: debugserver_client_t, env: *const c_char, response: *mut *mut c_char) -> debugserver_error_t;
+
+    pub fn debugserver_command_new(name: *const c_char, argc: c_int, argv: *mut *mut c_char, command: *mut debugserver_command_t) -> debugserver_error_t;
+    pub fn debugserver_command_free(command: debugserver_command_t) -> debugserver_error_t;
+
+    pub fn debugserver_encode_string(buffer: *const c_char, encoded_buffer: *mut *mut c_char, encoded_length: *mut u32);
+    pub fn debugserver_decode_string(encoded_buffer: *const c_char, encoded_length: usize, buffer: *mut *mut c_char);
+}
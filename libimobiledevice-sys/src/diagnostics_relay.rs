@@ -8,24 +8,17 @@ use std::os::raw::{c_char, c_void, c_int};
 
 pub const DIAGNOSTICS_RELAY_SERVICE_NAME: &'static [u8] = b"com.apple.mobile.diagnostics_relay\0";
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-#[repr(i32)]
-pub enum diagnostics_relay_error_t {
-    Success = 0,
-    InvalidArg = -1,
-    PlistError = -2,
-    MuxError = -3,
-    UnknownRequest = -4,
-    UnknownError = -256,
+error_code! {
+    diagnostics_relay_error_t {
+        Success = 0 => DIAGNOSTICS_RELAY_E_SUCCESS,
+        InvalidArg = -1 => DIAGNOSTICS_RELAY_E_INVALID_ARG,
+        PlistError = -2 => DIAGNOSTICS_RELAY_E_PLIST_ERROR,
+        MuxError = -3 => DIAGNOSTICS_RELAY_E_MUX_ERROR,
+        UnknownRequest = -4 => DIAGNOSTICS_RELAY_E_UNKNOWN_REQUEST,
+        UnknownError = -256 => DIAGNOSTICS_RELAY_E_UNKNOWN_ERROR,
+    }
 }
 
-pub const DIAGNOSTICS_RELAY_E_SUCCESS: diagnostics_relay_error_t = diagnostics_relay_error_t::Success;
-pub const DIAGNOSTICS_RELAY_E_INVALID_ARG: diagnostics_relay_error_t = diagnostics_relay_error_t::InvalidArg;
-pub const DIAGNOSTICS_RELAY_E_PLIST_ERROR: diagnostics_relay_error_t = diagnostics_relay_error_t::PlistError;
-pub const DIAGNOSTICS_RELAY_E_MUX_ERROR: diagnostics_relay_error_t = diagnostics_relay_error_t::MuxError;
-pub const DIAGNOSTICS_RELAY_E_UNKNOWN_REQUEST: diagnostics_relay_error_t = diagnostics_relay_error_t::UnknownRequest;
-pub const DIAGNOSTICS_RELAY_E_UNKNOWN_ERROR: diagnostics_relay_error_t = diagnostics_relay_error_t::UnknownError;
-
 pub const DIAGNOSTICS_RELAY_ACTION_FLAG_WAIT_FOR_DISCONNECT: c_int = 2;
 pub const DIAGNOSTICS_RELAY_ACTION_FLAG_DISPLAY_PASS: c_int = 4;
 pub const DIAGNOSTICS_RELAY_ACTION_FLAG_DISPLAY_FAIL: c_int = 8;
@@ -0,0 +1,37 @@
+//! Bindings to `file_relay.h`.
+
+use idevice::{idevice_t, idevice_connection_t};
+use lockdown::lockdownd_service_descriptor_t;
+
+use std::os::raw::{c_char, c_uint, c_void};
+
+pub const FILE_RELAY_SERVICE_NAME: &'static [u8] = b"com.apple.mobile.file_relay\0";
+
+error_code! {
+    file_relay_error_t {
+        Success = 0 => FILE_RELAY_E_SUCCESS,
+        InvalidArg = -1 => FILE_RELAY_E_INVALID_ARG,
+        PlistError = -2 => FILE_RELAY_E_PLIST_ERROR,
+        MuxError = -3 => FILE_RELAY_E_MUX_ERROR,
+        InvalidSource = -4 => FILE_RELAY_E_INVALID_SOURCE,
+        StagingEmpty = -5 => FILE_RELAY_E_STAGING_EMPTY,
+        PermissionDenied = -6 => FILE_RELAY_E_PERMISSION_DENIED,
+        UnknownError = -256 => FILE_RELAY_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct file_relay_client_private(c_void);
+pub type file_relay_client_t = *mut file_relay_client_private;
+
+extern "C" {
+    pub fn file_relay_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut file_relay_client_t) -> file_relay_error_t;
+    pub fn file_relay_client_start_service(device: idevice_t, client: *mut file_relay_client_t, label: *const c_char) -> file_relay_error_t;
+    pub fn file_relay_client_free(client: file_relay_client_t) -> file_relay_error_t;
+
+    /// Requests a NULL-terminated list of sources such as `"CrashReporter"`. On success, a
+    /// gzipped cpio archive can be read from `connection`, which is owned by the client.
+    pub fn file_relay_request_sources(client: file_relay_client_t, sources: *mut *const c_char, connection: *mut idevice_connection_t) -> file_relay_error_t;
+    pub fn file_relay_request_sources_timeout(client: file_relay_client_t, sources: *mut *const c_char, connection: *mut idevice_connection_t, timeout: c_uint) -> file_relay_error_t;
+}
@@ -0,0 +1,38 @@
+//! Bindings to `heartbeat.h`.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_char, c_void};
+
+pub const HEARTBEAT_SERVICE_NAME: &'static [u8] = b"com.apple.mobile.heartbeat\0";
+
+error_code! {
+    heartbeat_error_t {
+        Success = 0 => HEARTBEAT_E_SUCCESS,
+        InvalidArg = -1 => HEARTBEAT_E_INVALID_ARG,
+        PlistError = -2 => HEARTBEAT_E_PLIST_ERROR,
+        MuxError = -3 => HEARTBEAT_E_MUX_ERROR,
+        SslError = -4 => HEARTBEAT_E_SSL_ERROR,
+        NotEnoughData = -5 => HEARTBEAT_E_NOT_ENOUGH_DATA,
+        Timeout = -6 => HEARTBEAT_E_TIMEOUT,
+        UnknownError = -256 => HEARTBEAT_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct heartbeat_client_private(c_void);
+pub type heartbeat_client_t = *mut heartbeat_client_private;
+
+extern "C" {
+    pub fn heartbeat_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut heartbeat_client_t) -> heartbeat_error_t;
+    pub fn heartbeat_client_start_service(device: idevice_t, client: *mut heartbeat_client_t, label: *const c_char) -> heartbeat_error_t;
+    pub fn heartbeat_client_free(client: heartbeat_client_t) -> heartbeat_error_t;
+
+    /// Sends a message, typically `{"Command": "Polo"}` in reply to the device's `"Marco"`.
+    pub fn heartbeat_send(client: heartbeat_client_t, plist: plist_t) -> heartbeat_error_t;
+    pub fn heartbeat_receive(client: heartbeat_client_t, plist: *mut plist_t) -> heartbeat_error_t;
+    pub fn heartbeat_receive_with_timeout(client: heartbeat_client_t, plist: *mut plist_t, timeout_ms: u32) -> heartbeat_error_t;
+}
@@ -0,0 +1,42 @@
+//! Bindings to `house_arrest.h`.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+#[cfg(feature="afc")] use afc::{afc_client_t, afc_error_t};
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_char, c_void};
+
+pub const HOUSE_ARREST_SERVICE_NAME: &'static [u8] = b"com.apple.mobile.house_arrest\0";
+
+error_code! {
+    house_arrest_error_t {
+        Success = 0 => HOUSE_ARREST_E_SUCCESS,
+        InvalidArg = -1 => HOUSE_ARREST_E_INVALID_ARG,
+        PlistError = -2 => HOUSE_ARREST_E_PLIST_ERROR,
+        ConnFailed = -3 => HOUSE_ARREST_E_CONN_FAILED,
+        InvalidMode = -4 => HOUSE_ARREST_E_INVALID_MODE,
+        UnknownError = -256 => HOUSE_ARREST_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct house_arrest_client_private(c_void);
+pub type house_arrest_client_t = *mut house_arrest_client_private;
+
+extern "C" {
+    pub fn house_arrest_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut house_arrest_client_t) -> house_arrest_error_t;
+    pub fn house_arrest_client_start_service(device: idevice_t, client: *mut house_arrest_client_t, label: *const c_char) -> house_arrest_error_t;
+    pub fn house_arrest_client_free(client: house_arrest_client_t) -> house_arrest_error_t;
+
+    pub fn house_arrest_send_request(client: house_arrest_client_t, dict: plist_t) -> house_arrest_error_t;
+    /// Sends a command such as `"VendContainer"` or `"VendDocuments"` for the given app.
+    pub fn house_arrest_send_command(client: house_arrest_client_t, command: *const c_char, appid: *const c_char) -> house_arrest_error_t;
+    pub fn house_arrest_get_result(client: house_arrest_client_t, dict: *mut plist_t) -> house_arrest_error_t;
+
+    /// Turns a house arrest connection into an AFC client for the vended container. The house
+    /// arrest client must not be used afterwards except to free it, after the AFC client.
+    #[cfg(feature="afc")]
+    pub fn afc_client_new_from_house_arrest_client(client: house_arrest_client_t, afc_client: *mut afc_client_t) -> afc_error_t;
+}
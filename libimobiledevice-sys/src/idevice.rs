@@ -2,26 +2,19 @@
 
 use std::os::raw::{c_void, c_char, c_int, c_uint};
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-#[repr(i32)]
-pub enum idevice_error_t {
-    Success = 0,
-    InvalidArg = -1,
-    UnknownError = -2,
-    NoDevice = -3,
-    NotEnoughData = -4,
-    BadHeader = -5,
-    SslError = -6,
+error_code! {
+    idevice_error_t {
+        Success = 0 => IDEVICE_E_SUCCESS,
+        InvalidArg = -1 => IDEVICE_E_INVALID_ARG,
+        UnknownError = -2 => IDEVICE_E_UNKNOWN_ERROR,
+        NoDevice = -3 => IDEVICE_E_NO_DEVICE,
+        NotEnoughData = -4 => IDEVICE_E_NOT_ENOUGH_DATA,
+        BadHeader = -5 => IDEVICE_E_BAD_HEADER,
+        SslError = -6 => IDEVICE_E_SSL_ERROR,
+        Timeout = -7 => IDEVICE_E_TIMEOUT,
+    }
 }
 
-pub const IDEVICE_E_SUCCESS: idevice_error_t = idevice_error_t::Success;
-pub const IDEVICE_E_INVALID_ARG: idevice_error_t = idevice_error_t::InvalidArg;
-pub const IDEVICE_E_UNKNOWN_ERROR: idevice_error_t = idevice_error_t::UnknownError;
-pub const IDEVICE_E_NO_DEVICE: idevice_error_t = idevice_error_t::NoDevice;
-pub const IDEVICE_E_NOT_ENOUGH_DATA: idevice_error_t = idevice_error_t::NotEnoughData;
-pub const IDEVICE_E_BAD_HEADER: idevice_error_t = idevice_error_t::BadHeader;
-pub const IDEVICE_E_SSL_ERROR: idevice_error_t = idevice_error_t::SslError;
-
 #[doc(hidden)]
 #[repr(C)]
 pub struct idevice_private(c_void);
@@ -32,15 +25,13 @@ pub type idevice_t = *mut idevice_private;
 pub struct idevice_connection_private(c_void);
 pub type idevice_connection_t = *mut idevice_connection_private;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-#[repr(u32)]
-pub enum idevice_event_type {
-    DeviceAdd = 1,
-    DeviceRemove = 2,
-}
+/// The kind of a device event. Kept as a plain integer since newer libraries may add event types.
+pub type idevice_event_type = c_uint;
 
-pub const IDEVICE_DEVICE_ADD: idevice_event_type = idevice_event_type::DeviceAdd;
-pub const IDEVICE_DEVICE_REMOVE: idevice_event_type = idevice_event_type::DeviceRemove;
+pub const IDEVICE_DEVICE_ADD: idevice_event_type = 1;
+pub const IDEVICE_DEVICE_REMOVE: idevice_event_type = 2;
+/// Sent by libimobiledevice 1.3 and later once the device has been paired with this host.
+pub const IDEVICE_DEVICE_PAIRED: idevice_event_type = 3;
 
 #[repr(C)]
 pub struct idevice_event_t {
@@ -49,8 +40,44 @@ pub struct idevice_event_t {
     pub conn_type: c_int,
 }
 
+/// Callback invoked on device add/remove events.
+///
+/// This is deliberately `extern "C"` rather than `extern "C-unwind"`: the callback runs on
+/// libusbmuxd's listener thread with only C frames above it, so there is nothing an unwind could
+/// propagate to. A panic escaping the callback aborts the process instead.
 pub type idevice_event_cb_t = unsafe extern "C" fn(event: *const idevice_event_t, user_data: *mut c_void);
 
+/// How a device is connected to the host. Kept as a plain integer since newer libraries may add
+/// connection types.
+#[cfg(feature="libimobiledevice-1-3")]
+pub type idevice_connection_type = c_uint;
+
+#[cfg(feature="libimobiledevice-1-3")]
+pub const CONNECTION_USBMUXD: idevice_connection_type = 1;
+#[cfg(feature="libimobiledevice-1-3")]
+pub const CONNECTION_NETWORK: idevice_connection_type = 2;
+
+#[cfg(feature="libimobiledevice-1-3")]
+#[repr(C)]
+pub struct idevice_info {
+    pub udid: *mut c_char,
+    pub conn_type: idevice_connection_type,
+    pub conn_data: *mut c_void,
+}
+#[cfg(feature="libimobiledevice-1-3")]
+pub type idevice_info_t = *mut idevice_info;
+
+/// Flags for `idevice_new_with_options`.
+#[cfg(feature="libimobiledevice-1-3")]
+pub type idevice_options = c_uint;
+
+#[cfg(feature="libimobiledevice-1-3")]
+pub const IDEVICE_LOOKUP_USBMUX: idevice_options = 1 << 1;
+#[cfg(feature="libimobiledevice-1-3")]
+pub const IDEVICE_LOOKUP_NETWORK: idevice_options = 1 << 2;
+#[cfg(feature="libimobiledevice-1-3")]
+pub const IDEVICE_LOOKUP_PREFER_NETWORK: idevice_options = 1 << 3;
+
 extern "C" {
     pub fn idevice_set_debug_level(level: c_int);
 
@@ -59,8 +86,14 @@ extern "C" {
 
     pub fn idevice_get_device_list(devices: *mut *mut *mut c_char, count: *mut c_int) -> idevice_error_t;
     pub fn idevice_device_list_free(devices: *mut *mut c_char) -> idevice_error_t;
+    #[cfg(feature="libimobiledevice-1-3")]
+    pub fn idevice_get_device_list_extended(devices: *mut *mut idevice_info_t, count: *mut c_int) -> idevice_error_t;
+    #[cfg(feature="libimobiledevice-1-3")]
+    pub fn idevice_device_list_extended_free(devices: *mut idevice_info_t) -> idevice_error_t;
 
     pub fn idevice_new(device: *mut idevice_t, udid: *const c_char) -> idevice_error_t;
+    #[cfg(feature="libimobiledevice-1-3")]
+    pub fn idevice_new_with_options(device: *mut idevice_t, udid: *const c_char, options: idevice_options) -> idevice_error_t;
     pub fn idevice_free(device: idevice_t) -> idevice_error_t;
 
     pub fn idevice_connect(device: idevice_t, port: u16, connection: *mut idevice_connection_t) -> idevice_error_t;
@@ -72,9 +105,9 @@ extern "C" {
 
     pub fn idevice_connection_enable_ssl(connection: idevice_connection_t) -> idevice_error_t;
     pub fn idevice_connection_disable_ssl(connection: idevice_connection_t) -> idevice_error_t;
+    pub fn idevice_connection_get_fd(connection: idevice_connection_t, fd: *mut c_int) -> idevice_error_t;
 
     pub fn idevice_get_handle(device: idevice_t, handle: *mut u32) -> idevice_error_t;
     pub fn idevice_get_udid(device: idevice_t, udid: *mut *mut c_char) -> idevice_error_t;
 }
 
-
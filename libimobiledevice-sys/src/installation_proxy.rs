@@ -0,0 +1,124 @@
+//! Bindings to `installation_proxy.h`.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_char, c_int, c_void};
+
+pub const INSTPROXY_SERVICE_NAME: &'static [u8] = b"com.apple.mobile.installation_proxy\0";
+
+error_code! {
+    instproxy_error_t {
+        Success = 0 => INSTPROXY_E_SUCCESS,
+        InvalidArg = -1 => INSTPROXY_E_INVALID_ARG,
+        PlistError = -2 => INSTPROXY_E_PLIST_ERROR,
+        ConnFailed = -3 => INSTPROXY_E_CONN_FAILED,
+        OpInProgress = -4 => INSTPROXY_E_OP_IN_PROGRESS,
+        OpFailed = -5 => INSTPROXY_E_OP_FAILED,
+        ReceiveTimeout = -6 => INSTPROXY_E_RECEIVE_TIMEOUT,
+        // Errors reported by the device.
+        AlreadyArchived = -7 => INSTPROXY_E_ALREADY_ARCHIVED,
+        ApiInternalError = -8 => INSTPROXY_E_API_INTERNAL_ERROR,
+        ApplicationAlreadyInstalled = -9 => INSTPROXY_E_APPLICATION_ALREADY_INSTALLED,
+        ApplicationMoveFailed = -10 => INSTPROXY_E_APPLICATION_MOVE_FAILED,
+        ApplicationSinfCaptureFailed = -11 => INSTPROXY_E_APPLICATION_SINF_CAPTURE_FAILED,
+        ApplicationSandboxFailed = -12 => INSTPROXY_E_APPLICATION_SANDBOX_FAILED,
+        ApplicationVerificationFailed = -13 => INSTPROXY_E_APPLICATION_VERIFICATION_FAILED,
+        ArchiveDestructionFailed = -14 => INSTPROXY_E_ARCHIVE_DESTRUCTION_FAILED,
+        BundleVerificationFailed = -15 => INSTPROXY_E_BUNDLE_VERIFICATION_FAILED,
+        CarrierBundleCopyFailed = -16 => INSTPROXY_E_CARRIER_BUNDLE_COPY_FAILED,
+        CarrierBundleDirectoryCreationFailed = -17 => INSTPROXY_E_CARRIER_BUNDLE_DIRECTORY_CREATION_FAILED,
+        CarrierBundleMissingSupportedSims = -18 => INSTPROXY_E_CARRIER_BUNDLE_MISSING_SUPPORTED_SIMS,
+        CommCenterNotificationFailed = -19 => INSTPROXY_E_COMM_CENTER_NOTIFICATION_FAILED,
+        ContainerCreationFailed = -20 => INSTPROXY_E_CONTAINER_CREATION_FAILED,
+        ContainerP0wnFailed = -21 => INSTPROXY_E_CONTAINER_P0WN_FAILED,
+        ContainerRemovalFailed = -22 => INSTPROXY_E_CONTAINER_REMOVAL_FAILED,
+        EmbeddedProfileInstallFailed = -23 => INSTPROXY_E_EMBEDDED_PROFILE_INSTALL_FAILED,
+        ExecutableTwiddleFailed = -24 => INSTPROXY_E_EXECUTABLE_TWIDDLE_FAILED,
+        ExistenceCheckFailed = -25 => INSTPROXY_E_EXISTENCE_CHECK_FAILED,
+        InstallMapUpdateFailed = -26 => INSTPROXY_E_INSTALL_MAP_UPDATE_FAILED,
+        ManifestCaptureFailed = -27 => INSTPROXY_E_MANIFEST_CAPTURE_FAILED,
+        MapGenerationFailed = -28 => INSTPROXY_E_MAP_GENERATION_FAILED,
+        MissingBundleExecutable = -29 => INSTPROXY_E_MISSING_BUNDLE_EXECUTABLE,
+        MissingBundleIdentifier = -30 => INSTPROXY_E_MISSING_BUNDLE_IDENTIFIER,
+        MissingBundlePath = -31 => INSTPROXY_E_MISSING_BUNDLE_PATH,
+        MissingContainer = -32 => INSTPROXY_E_MISSING_CONTAINER,
+        NotificationFailed = -33 => INSTPROXY_E_NOTIFICATION_FAILED,
+        PackageExtractionFailed = -34 => INSTPROXY_E_PACKAGE_EXTRACTION_FAILED,
+        PackageInspectionFailed = -35 => INSTPROXY_E_PACKAGE_INSPECTION_FAILED,
+        PackageMoveFailed = -36 => INSTPROXY_E_PACKAGE_MOVE_FAILED,
+        PathConversionFailed = -37 => INSTPROXY_E_PATH_CONVERSION_FAILED,
+        RestoreContainerFailed = -38 => INSTPROXY_E_RESTORE_CONTAINER_FAILED,
+        SeatbeltProfileRemovalFailed = -39 => INSTPROXY_E_SEATBELT_PROFILE_REMOVAL_FAILED,
+        StageCreationFailed = -40 => INSTPROXY_E_STAGE_CREATION_FAILED,
+        SymlinkFailed = -41 => INSTPROXY_E_SYMLINK_FAILED,
+        UnknownCommand = -42 => INSTPROXY_E_UNKNOWN_COMMAND,
+        ItunesArtworkCaptureFailed = -43 => INSTPROXY_E_ITUNES_ARTWORK_CAPTURE_FAILED,
+        ItunesMetadataCaptureFailed = -44 => INSTPROXY_E_ITUNES_METADATA_CAPTURE_FAILED,
+        DeviceOsVersionTooLow = -45 => INSTPROXY_E_DEVICE_OS_VERSION_TOO_LOW,
+        DeviceFamilyNotSupported = -46 => INSTPROXY_E_DEVICE_FAMILY_NOT_SUPPORTED,
+        PackagePatchFailed = -47 => INSTPROXY_E_PACKAGE_PATCH_FAILED,
+        IncorrectArchitecture = -48 => INSTPROXY_E_INCORRECT_ARCHITECTURE,
+        PluginCopyFailed = -49 => INSTPROXY_E_PLUGIN_COPY_FAILED,
+        BreadcrumbFailed = -50 => INSTPROXY_E_BREADCRUMB_FAILED,
+        BreadcrumbUnlockFailed = -51 => INSTPROXY_E_BREADCRUMB_UNLOCK_FAILED,
+        GeojsonCaptureFailed = -52 => INSTPROXY_E_GEOJSON_CAPTURE_FAILED,
+        NewsstandArtworkCaptureFailed = -53 => INSTPROXY_E_NEWSSTAND_ARTWORK_CAPTURE_FAILED,
+        MissingCommand = -54 => INSTPROXY_E_MISSING_COMMAND,
+        NotEntitled = -55 => INSTPROXY_E_NOT_ENTITLED,
+        MissingPackagePath = -56 => INSTPROXY_E_MISSING_PACKAGE_PATH,
+        MissingContainerPath = -57 => INSTPROXY_E_MISSING_CONTAINER_PATH,
+        MissingApplicationIdentifier = -58 => INSTPROXY_E_MISSING_APPLICATION_IDENTIFIER,
+        MissingAttributeValue = -59 => INSTPROXY_E_MISSING_ATTRIBUTE_VALUE,
+        LookupFailed = -60 => INSTPROXY_E_LOOKUP_FAILED,
+        DictCreationFailed = -61 => INSTPROXY_E_DICT_CREATION_FAILED,
+        InstallProhibited = -62 => INSTPROXY_E_INSTALL_PROHIBITED,
+        UninstallProhibited = -63 => INSTPROXY_E_UNINSTALL_PROHIBITED,
+        MissingBundleVersion = -64 => INSTPROXY_E_MISSING_BUNDLE_VERSION,
+        UnknownError = -256 => INSTPROXY_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct instproxy_client_private(c_void);
+pub type instproxy_client_t = *mut instproxy_client_private;
+
+/// Reports the progress of an operation. Passing `None` to an operation makes it synchronous.
+pub type instproxy_status_cb_t = Option<unsafe extern "C" fn(command: plist_t, status: plist_t, user_data: *mut c_void)>;
+
+extern "C" {
+    pub fn instproxy_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut instproxy_client_t) -> instproxy_error_t;
+    pub fn instproxy_client_start_service(device: idevice_t, client: *mut instproxy_client_t, label: *const c_char) -> instproxy_error_t;
+    pub fn instproxy_client_free(client: instproxy_client_t) -> instproxy_error_t;
+
+    pub fn instproxy_browse(client: instproxy_client_t, client_options: plist_t, result: *mut plist_t) -> instproxy_error_t;
+    pub fn instproxy_browse_with_callback(client: instproxy_client_t, client_options: plist_t, status_cb: instproxy_status_cb_t, user_data: *mut c_void) -> instproxy_error_t;
+    pub fn instproxy_lookup(client: instproxy_client_t, appids: *mut *const c_char, client_options: plist_t, result: *mut plist_t) -> instproxy_error_t;
+    pub fn instproxy_install(client: instproxy_client_t, pkg_path: *const c_char, client_options: plist_t, status_cb: instproxy_status_cb_t, user_data: *mut c_void) -> instproxy_error_t;
+    pub fn instproxy_upgrade(client: instproxy_client_t, pkg_path: *const c_char, client_options: plist_t, status_cb: instproxy_status_cb_t, user_data: *mut c_void) -> instproxy_error_t;
+    pub fn instproxy_uninstall(client: instproxy_client_t, appid: *const c_char, client_options: plist_t, status_cb: instproxy_status_cb_t, user_data: *mut c_void) -> instproxy_error_t;
+
+    pub fn instproxy_lookup_archives(client: instproxy_client_t, client_options: plist_t, result: *mut plist_t) -> instproxy_error_t;
+    pub fn instproxy_archive(client: instproxy_client_t, appid: *const c_char, client_options: plist_t, status_cb: instproxy_status_cb_t, user_data: *mut c_void) -> instproxy_error_t;
+    pub fn instproxy_restore(client: instproxy_client_t, appid: *const c_char, client_options: plist_t, status_cb: instproxy_status_cb_t, user_data: *mut c_void) -> instproxy_error_t;
+    pub fn instproxy_remove_archive(client: instproxy_client_t, appid: *const c_char, client_options: plist_t, status_cb: instproxy_status_cb_t, user_data: *mut c_void) -> instproxy_error_t;
+
+    pub fn instproxy_check_capabilities_match(client: instproxy_client_t, capabilities: *mut *const c_char, client_options: plist_t, result: *mut plist_t) -> instproxy_error_t;
+    pub fn instproxy_client_get_path_for_bundle_identifier(client: instproxy_client_t, bundle_id: *const c_char, path: *mut *mut c_char) -> instproxy_error_t;
+
+    pub fn instproxy_status_get_error(status: plist_t, name: *mut *mut c_char, description: *mut *mut c_char, code: *mut u64) -> instproxy_error_t;
+    pub fn instproxy_status_get_name(status: plist_t, name: *mut *mut c_char);
+    pub fn instproxy_status_get_percent_complete(status: plist_t, percent: *mut c_int);
+    pub fn instproxy_status_get_current_list(status: plist_t, total: *mut u64, current_index: *mut u64, current_amount: *mut u64, list: *mut plist_t);
+    pub fn instproxy_command_get_name(command: plist_t, name: *mut *mut c_char);
+
+    pub fn instproxy_client_options_new() -> plist_t;
+    /// Adds key-value pairs to the options, terminated by a NULL key. Depending on the key, the
+    /// value is a `*const c_char`, a `c_int` or a `plist_t`.
+    pub fn instproxy_client_options_add(client_options: plist_t, ...);
+    /// Sets the attributes to return, as a NULL-terminated list of `*const c_char`.
+    pub fn instproxy_client_options_set_return_attributes(client_options: plist_t, ...);
+    pub fn instproxy_client_options_free(client_options: plist_t);
+}
@@ -1,14 +1,122 @@
+//! Bindings for libimobiledevice.
+//!
+//! Every service binding lives behind a cargo feature of the same name (`afc`,
+//! `diagnostics-relay`, ...), enabled by default. Build with `default-features = false` and
+//! pick the services you need to avoid referencing symbols missing from a stripped-down
+//! libimobiledevice.
+//!
+//! Functions added after libimobiledevice 1.2 are declared only with the `libimobiledevice-1-3`
+//! feature, which also makes the build require version 1.3.0 or later. Services added after 1.2
+//! (e.g. `mobileactivation`) are the exception: they are off by default and imply that feature.
+//!
+//! Error codes which were renumbered in 1.3 (several device-link services) follow the version of
+//! the library found by pkg-config instead, so they are correct even when the feature is off.
+
 #![allow(non_camel_case_types)]
 
 extern crate libplist_sys;
+#[cfg(all(unix, feature="runtime-detect"))] extern crate libloading;
+
+/// Defines an error code type returned by the C functions.
+///
+/// The C libraries may return codes unknown to these bindings (e.g. from a newer libimobiledevice),
+/// and receiving such a value as a Rust `enum` would be undefined behavior. Error codes are
+/// therefore newtypes over `i32` which can hold any value, with associated constants named like
+/// the former enum variants, and `X_E_*` constants like the C headers. Unknown codes are kept
+/// as-is and shown as `Unknown(code)` by `Debug`.
+///
+/// Attributes on a variant, such as `#[cfg]`, apply to both of its constants.
+macro_rules! error_code {
+    ($name:ident { $($(#[$attr:meta])* $variant:ident = $value:expr => $konst:ident,)* }) => {
+        #[derive(Copy, Clone, Eq, PartialEq, Hash)]
+        #[repr(transparent)]
+        pub struct $name(pub i32);
+
+        #[allow(non_upper_case_globals)]
+        impl $name {
+            $($(#[$attr])* pub const $variant: $name = $name($value);)*
+
+            /// The name of this error code, or `None` if the code is unknown to these bindings.
+            pub fn name(self) -> Option<&'static str> {
+                match self {
+                    $($(#[$attr])* $name::$variant => Some(stringify!($variant)),)*
+                    _ => None,
+                }
+            }
+
+            /// Whether this error code is known to these bindings.
+            pub fn is_known(self) -> bool {
+                self.name().is_some()
+            }
+        }
+
+        $($(#[$attr])* pub const $konst: $name = $name::$variant;)*
+
+        impl ::std::fmt::Debug for $name {
+            fn fmt(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                match self.name() {
+                    Some(name) => formatter.write_str(name),
+                    None => write!(formatter, "Unknown({})", self.0),
+                }
+            }
+        }
+
+        impl From<i32> for $name {
+            fn from(code: i32) -> $name {
+                $name(code)
+            }
+        }
+
+        impl From<$name> for i32 {
+            fn from(code: $name) -> i32 {
+                code.0
+            }
+        }
+    }
+}
 
 pub mod idevice;
 pub mod lockdown;
-pub mod afc;
-pub mod diagnostics_relay;
+#[cfg(feature="afc")] pub mod afc;
+#[cfg(feature="bt-packet-logger")] pub mod bt_packet_logger;
+#[cfg(feature="companion-proxy")] pub mod companion_proxy;
+#[cfg(feature="debugserver")] pub mod debugserver;
+#[cfg(feature="diagnostics-relay")] pub mod diagnostics_relay;
+#[cfg(feature="file-relay")] pub mod file_relay;
+#[cfg(feature="heartbeat")] pub mod heartbeat;
+#[cfg(feature="house-arrest")] pub mod house_arrest;
+#[cfg(feature="installation-proxy")] pub mod installation_proxy;
+#[cfg(feature="misagent")] pub mod misagent;
+#[cfg(feature="mobile-image-mounter")] pub mod mobile_image_mounter;
+#[cfg(feature="mobileactivation")] pub mod mobileactivation;
+#[cfg(feature="mobilebackup")] pub mod mobilebackup;
+#[cfg(feature="mobilebackup2")] pub mod mobilebackup2;
+#[cfg(feature="mobilesync")] pub mod mobilesync;
+#[cfg(feature="notification-proxy")] pub mod notification_proxy;
+#[cfg(feature="os-trace-relay")] pub mod os_trace_relay;
+#[cfg(feature="preboard")] pub mod preboard;
+#[cfg(feature="property-list-service")] pub mod property_list_service;
+#[cfg(feature="restore")] pub mod restore;
+#[cfg(feature="reverse-proxy")] pub mod reverse_proxy;
+#[cfg(feature="sbservices")] pub mod sbservices;
+#[cfg(feature="screenshotr")] pub mod screenshotr;
+#[cfg(feature="service")] pub mod service;
+#[cfg(feature="syslog-relay")] pub mod syslog_relay;
+#[cfg(feature="webinspector")] pub mod webinspector;
+#[cfg(all(unix, feature="runtime-detect"))] pub mod capabilities;
 
 pub use idevice::*;
 
+#[test]
+fn test_error_code() {
+    assert_eq!(format!("{:?}", IDEVICE_E_NO_DEVICE), "NoDevice");
+    assert_eq!(idevice_error_t::from(-3), IDEVICE_E_NO_DEVICE);
+    let unknown = idevice_error_t::from(-1000);
+    assert!(!unknown.is_known());
+    assert_eq!(format!("{:?}", unknown), "Unknown(-1000)");
+    assert_eq!(i32::from(unknown), -1000);
+}
+
 #[test]
 fn test_validity() {
     unsafe {
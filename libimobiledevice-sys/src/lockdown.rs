@@ -1,92 +1,55 @@
 //! Bindings to `lockdown.h`.
 
 use std::os::raw::{c_void, c_char, c_int};
+#[cfg(feature="libimobiledevice-1-3")] use std::os::raw::c_uint;
 use idevice::{idevice_t};
 use libplist_sys::plist_t;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-#[repr(i32)]
-pub enum lockdownd_error_t {
-    // custom
-    Success = 0,
-    InvalidArg = -1,
-    InvalidConf = -2,
-    PlistError = -3,
-    PairingFailed = -4,
-    SslError = -5,
-    DictError = -6,
-    NotEnoughData = -7,
-    MuxError = -8,
-    NoRunningSession = -9,
-
-    // native
-    InvalidResponse = -10,
-    MissingKey = -11,
-    MissingValue = -12,
-    GetProhibited = -13,
-    SetProhibited = -14,
-    RemoveProhibited = -15,
-    ImmutableValue = -16,
-    PasswordProtected = -17,
-    UserDeniedPairing = -18,
-    PairingDialogResponsePending = -19,
-    MissingHostId = -20,
-    InvalidHostId = -21,
-    SessionActive = -22,
-    SessionInactive = -23,
-    MissingSessionId = -24,
-    InvalidSessionId = -25,
-    MissingService = -26,
-    InvalidService = -27,
-    ServiceLimit = -28,
-    MissingPairRecord = -29,
-    SavePairRecordFailed = -30,
-    InvalidPairRecord = -31,
-    InvalidActivationRecord = -32,
-    MissingActivationRecord = -33,
-    ServiceProhibited = -34,
-    EscrowLocked = -35,
-    UnknownError = -256,
+error_code! {
+    lockdownd_error_t {
+        // custom
+        Success = 0 => LOCKDOWN_E_SUCCESS,
+        InvalidArg = -1 => LOCKDOWN_E_INVALID_ARG,
+        InvalidConf = -2 => LOCKDOWN_E_INVALID_CONF,
+        PlistError = -3 => LOCKDOWN_E_PLIST_ERROR,
+        PairingFailed = -4 => LOCKDOWN_E_PAIRING_FAILED,
+        SslError = -5 => LOCKDOWN_E_SSL_ERROR,
+        DictError = -6 => LOCKDOWN_E_DICT_ERROR,
+        NotEnoughData = -7 => LOCKDOWN_E_NOT_ENOUGH_DATA,
+        MuxError = -8 => LOCKDOWN_E_MUX_ERROR,
+        NoRunningSession = -9 => LOCKDOWN_E_NO_RUNNING_SESSION,
+
+        // native
+        InvalidResponse = -10 => LOCKDOWN_E_INVALID_RESPONSE,
+        MissingKey = -11 => LOCKDOWN_E_MISSING_KEY,
+        MissingValue = -12 => LOCKDOWN_E_MISSING_VALUE,
+        GetProhibited = -13 => LOCKDOWN_E_GET_PROHIBITED,
+        SetProhibited = -14 => LOCKDOWN_E_SET_PROHIBITED,
+        RemoveProhibited = -15 => LOCKDOWN_E_REMOVE_PROHIBITED,
+        ImmutableValue = -16 => LOCKDOWN_E_IMMUTABLE_VALUE,
+        PasswordProtected = -17 => LOCKDOWN_E_PASSWORD_PROTECTED,
+        UserDeniedPairing = -18 => LOCKDOWN_E_USER_DENIED_PAIRING,
+        PairingDialogResponsePending = -19 => LOCKDOWN_E_PAIRING_DIALOG_RESPONSE_PENDING,
+        MissingHostId = -20 => LOCKDOWN_E_MISSING_HOST_ID,
+        InvalidHostId = -21 => LOCKDOWN_E_INVALID_HOST_ID,
+        SessionActive = -22 => LOCKDOWN_E_SESSION_ACTIVE,
+        SessionInactive = -23 => LOCKDOWN_E_SESSION_INACTIVE,
+        MissingSessionId = -24 => LOCKDOWN_E_MISSING_SESSION_ID,
+        InvalidSessionId = -25 => LOCKDOWN_E_INVALID_SESSION_ID,
+        MissingService = -26 => LOCKDOWN_E_MISSING_SERVICE,
+        InvalidService = -27 => LOCKDOWN_E_INVALID_SERVICE,
+        ServiceLimit = -28 => LOCKDOWN_E_SERVICE_LIMIT,
+        MissingPairRecord = -29 => LOCKDOWN_E_MISSING_PAIR_RECORD,
+        SavePairRecordFailed = -30 => LOCKDOWN_E_SAVE_PAIR_RECORD_FAILED,
+        InvalidPairRecord = -31 => LOCKDOWN_E_INVALID_PAIR_RECORD,
+        InvalidActivationRecord = -32 => LOCKDOWN_E_INVALID_ACTIVATION_RECORD,
+        MissingActivationRecord = -33 => LOCKDOWN_E_MISSING_ACTIVATION_RECORD,
+        ServiceProhibited = -34 => LOCKDOWN_E_SERVICE_PROHIBITED,
+        EscrowLocked = -35 => LOCKDOWN_E_ESCROW_LOCKED,
+        UnknownError = -256 => LOCKDOWN_E_UNKNOWN_ERROR,
+    }
 }
 
-pub const LOCKDOWN_E_SUCCESS: lockdownd_error_t = lockdownd_error_t::Success;
-pub const LOCKDOWN_E_INVALID_ARG: lockdownd_error_t = lockdownd_error_t::InvalidArg;
-pub const LOCKDOWN_E_INVALID_CONF: lockdownd_error_t = lockdownd_error_t::InvalidConf;
-pub const LOCKDOWN_E_PLIST_ERROR: lockdownd_error_t = lockdownd_error_t::PlistError;
-pub const LOCKDOWN_E_PAIRING_FAILED: lockdownd_error_t = lockdownd_error_t::PairingFailed;
-pub const LOCKDOWN_E_SSL_ERROR: lockdownd_error_t = lockdownd_error_t::SslError;
-pub const LOCKDOWN_E_DICT_ERROR: lockdownd_error_t = lockdownd_error_t::DictError;
-pub const LOCKDOWN_E_NOT_ENOUGH_DATA: lockdownd_error_t = lockdownd_error_t::NotEnoughData;
-pub const LOCKDOWN_E_MUX_ERROR: lockdownd_error_t = lockdownd_error_t::MuxError;
-pub const LOCKDOWN_E_NO_RUNNING_SESSION: lockdownd_error_t = lockdownd_error_t::NoRunningSession;
-pub const LOCKDOWN_E_INVALID_RESPONSE: lockdownd_error_t = lockdownd_error_t::InvalidResponse;
-pub const LOCKDOWN_E_MISSING_KEY: lockdownd_error_t = lockdownd_error_t::MissingKey;
-pub const LOCKDOWN_E_MISSING_VALUE: lockdownd_error_t = lockdownd_error_t::MissingValue;
-pub const LOCKDOWN_E_GET_PROHIBITED: lockdownd_error_t = lockdownd_error_t::GetProhibited;
-pub const LOCKDOWN_E_SET_PROHIBITED: lockdownd_error_t = lockdownd_error_t::SetProhibited;
-pub const LOCKDOWN_E_REMOVE_PROHIBITED: lockdownd_error_t = lockdownd_error_t::RemoveProhibited;
-pub const LOCKDOWN_E_IMMUTABLE_VALUE: lockdownd_error_t = lockdownd_error_t::ImmutableValue;
-pub const LOCKDOWN_E_PASSWORD_PROTECTED: lockdownd_error_t = lockdownd_error_t::PasswordProtected;
-pub const LOCKDOWN_E_USER_DENIED_PAIRING: lockdownd_error_t = lockdownd_error_t::UserDeniedPairing;
-pub const LOCKDOWN_E_PAIRING_DIALOG_RESPONSE_PENDING: lockdownd_error_t = lockdownd_error_t::PairingDialogResponsePending;
-pub const LOCKDOWN_E_MISSING_HOST_ID: lockdownd_error_t = lockdownd_error_t::MissingHostId;
-pub const LOCKDOWN_E_INVALID_HOST_ID: lockdownd_error_t = lockdownd_error_t::InvalidHostId;
-pub const LOCKDOWN_E_SESSION_ACTIVE: lockdownd_error_t = lockdownd_error_t::SessionActive;
-pub const LOCKDOWN_E_SESSION_INACTIVE: lockdownd_error_t = lockdownd_error_t::SessionInactive;
-pub const LOCKDOWN_E_MISSING_SESSION_ID: lockdownd_error_t = lockdownd_error_t::MissingSessionId;
-pub const LOCKDOWN_E_INVALID_SESSION_ID: lockdownd_error_t = lockdownd_error_t::InvalidSessionId;
-pub const LOCKDOWN_E_MISSING_SERVICE: lockdownd_error_t = lockdownd_error_t::MissingService;
-pub const LOCKDOWN_E_INVALID_SERVICE: lockdownd_error_t = lockdownd_error_t::InvalidService;
-pub const LOCKDOWN_E_SERVICE_LIMIT: lockdownd_error_t = lockdownd_error_t::ServiceLimit;
-pub const LOCKDOWN_E_MISSING_PAIR_RECORD: lockdownd_error_t = lockdownd_error_t::MissingPairRecord;
-pub const LOCKDOWN_E_SAVE_PAIR_RECORD_FAILED: lockdownd_error_t = lockdownd_error_t::SavePairRecordFailed;
-pub const LOCKDOWN_E_INVALID_PAIR_RECORD: lockdownd_error_t = lockdownd_error_t::InvalidPairRecord;
-pub const LOCKDOWN_E_INVALID_ACTIVATION_RECORD: lockdownd_error_t = lockdownd_error_t::InvalidActivationRecord;
-pub const LOCKDOWN_E_MISSING_ACTIVATION_RECORD: lockdownd_error_t = lockdownd_error_t::MissingActivationRecord;
-pub const LOCKDOWN_E_SERVICE_PROHIBITED: lockdownd_error_t = lockdownd_error_t::ServiceProhibited;
-pub const LOCKDOWN_E_ESCROW_LOCKED: lockdownd_error_t = lockdownd_error_t::EscrowLocked;
-pub const LOCKDOWN_E_UNKNOWN_ERROR: lockdownd_error_t = lockdownd_error_t::UnknownError;
-
 #[doc(hidden)]
 #[repr(C)]
 pub struct lockdownd_client_private(c_void);
@@ -109,6 +72,22 @@ pub struct lockdownd_service_descriptor {
 }
 pub type lockdownd_service_descriptor_t = *mut lockdownd_service_descriptor;
 
+/// What a `lockdownd_cu_pairing_cb_t` is called for.
+#[cfg(feature="libimobiledevice-1-3")]
+pub type lockdownd_cu_pairing_cb_type_t = c_uint;
+/// The PIN shown on the device must be written to `data_ptr` as a NUL-terminated string.
+#[cfg(feature="libimobiledevice-1-3")]
+pub const LOCKDOWN_CU_PAIRING_PIN_REQUESTED: lockdownd_cu_pairing_cb_type_t = 0;
+/// `data_ptr` points to a `plist_t` describing the device.
+#[cfg(feature="libimobiledevice-1-3")]
+pub const LOCKDOWN_CU_PAIRING_DEVICE_INFO: lockdownd_cu_pairing_cb_type_t = 1;
+/// `data_ptr` points to an error message of `*data_size` bytes.
+#[cfg(feature="libimobiledevice-1-3")]
+pub const LOCKDOWN_CU_PAIRING_ERROR: lockdownd_cu_pairing_cb_type_t = 2;
+
+#[cfg(feature="libimobiledevice-1-3")]
+pub type lockdownd_cu_pairing_cb_t = unsafe extern "C" fn(cb_type: lockdownd_cu_pairing_cb_type_t, user_data: *mut c_void, data_ptr: *mut c_void, data_size: *mut c_uint);
+
 extern "C" {
     pub fn lockdownd_client_new(device: idevice_t, client: *mut lockdownd_client_t, label: *const c_char) -> lockdownd_error_t;
     pub fn lockdownd_client_new_with_handshake(device: idevice_t, client: *mut lockdownd_client_t, label: *const c_char) -> lockdownd_error_t;
@@ -132,6 +111,23 @@ extern "C" {
     pub fn lockdownd_pair(client: lockdownd_client_t, pair_record: lockdownd_pair_record_t) -> lockdownd_error_t;
     pub fn lockdownd_validate_pair(client: lockdownd_client_t, pair_record: lockdownd_pair_record_t) -> lockdownd_error_t;
     pub fn lockdownd_unpair(client: lockdownd_client_t, pair_record: lockdownd_pair_record_t) -> lockdownd_error_t;
+    /// Pairs with an options dictionary, e.g. `{"ExtendedPairingErrors": true}`, also returning the
+    /// device's response if `response` is not NULL.
+    #[cfg(feature="libimobiledevice-1-3")]
+    pub fn lockdownd_pair_with_options(client: lockdownd_client_t, pair_record: lockdownd_pair_record_t, options: plist_t, response: *mut plist_t) -> lockdownd_error_t;
+
+    // "Pair setup" with a PIN, for devices like the Apple TV connected over the network. These
+    // are newer than libimobiledevice 1.3.0, and need a libimobiledevice built from a later
+    // source tree.
+    #[cfg(feature="libimobiledevice-1-3")]
+    pub fn lockdownd_cu_pairing_create(client: lockdownd_client_t, pairing_callback: lockdownd_cu_pairing_cb_t, cb_user_data: *mut c_void, host_info: plist_t, acl: plist_t) -> lockdownd_error_t;
+    #[cfg(feature="libimobiledevice-1-3")]
+    pub fn lockdownd_cu_send_request_and_get_reply(client: lockdownd_client_t, request: *const c_char, request_payload: plist_t, reply: *mut plist_t) -> lockdownd_error_t;
+    #[cfg(feature="libimobiledevice-1-3")]
+    pub fn lockdownd_get_value_cu(client: lockdownd_client_t, domain: *const c_char, key: *const c_char, value: *mut plist_t) -> lockdownd_error_t;
+    /// Pairs using the session established by `lockdownd_cu_pairing_create`.
+    #[cfg(feature="libimobiledevice-1-3")]
+    pub fn lockdownd_pair_cu(client: lockdownd_client_t) -> lockdownd_error_t;
 
     pub fn lockdownd_activate(client: lockdownd_client_t, activation_record: plist_t) -> lockdownd_error_t;
     pub fn lockdownd_deactivate(client: lockdownd_client_t) -> lockdownd_error_t;
@@ -146,7 +142,6 @@ extern "C" {
     pub fn lockdownd_get_device_name(client: lockdownd_client_t, device_name: *mut *mut c_char) -> lockdownd_error_t;
 
     pub fn lockdownd_get_sync_data_classes(client: lockdownd_client_t, classes: *mut *mut *mut c_char, count: *mut c_int) -> lockdownd_error_t;
-    pub fn lockdownd_data_classes_free(client: lockdownd_client_t, classes: *mut *mut c_char);
+    pub fn lockdownd_data_classes_free(classes: *mut *mut c_char) -> lockdownd_error_t;
 }
 
-
@@ -0,0 +1,42 @@
+//! Bindings to `misagent.h`.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_char, c_int, c_void};
+
+pub const MISAGENT_SERVICE_NAME: &'static [u8] = b"com.apple.misagent\0";
+
+error_code! {
+    misagent_error_t {
+        Success = 0 => MISAGENT_E_SUCCESS,
+        InvalidArg = -1 => MISAGENT_E_INVALID_ARG,
+        PlistError = -2 => MISAGENT_E_PLIST_ERROR,
+        ConnFailed = -3 => MISAGENT_E_CONN_FAILED,
+        RequestFailed = -4 => MISAGENT_E_REQUEST_FAILED,
+        UnknownError = -256 => MISAGENT_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct misagent_client_private(c_void);
+pub type misagent_client_t = *mut misagent_client_private;
+
+extern "C" {
+    pub fn misagent_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut misagent_client_t) -> misagent_error_t;
+    pub fn misagent_client_start_service(device: idevice_t, client: *mut misagent_client_t, label: *const c_char) -> misagent_error_t;
+    pub fn misagent_client_free(client: misagent_client_t) -> misagent_error_t;
+
+    /// Installs a provisioning profile, given as a plist data node.
+    pub fn misagent_install(client: misagent_client_t, profile: plist_t) -> misagent_error_t;
+    /// Copies the installed provisioning profiles as an array of data nodes. Not supported since
+    /// iOS 9.3; use `misagent_copy_all` instead.
+    pub fn misagent_copy(client: misagent_client_t, profiles: *mut plist_t) -> misagent_error_t;
+    #[cfg(feature="libimobiledevice-1-3")]
+    pub fn misagent_copy_all(client: misagent_client_t, profiles: *mut plist_t) -> misagent_error_t;
+    pub fn misagent_remove(client: misagent_client_t, profile_id: *const c_char) -> misagent_error_t;
+    /// The status code of the last request, to explain `MISAGENT_E_REQUEST_FAILED`.
+    pub fn misagent_get_status_code(client: misagent_client_t) -> c_int;
+}
@@ -0,0 +1,44 @@
+//! Bindings to `mobile_image_mounter.h`.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_char, c_void};
+
+pub const MOBILE_IMAGE_MOUNTER_SERVICE_NAME: &'static [u8] = b"com.apple.mobile.mobile_image_mounter\0";
+
+error_code! {
+    mobile_image_mounter_error_t {
+        Success = 0 => MOBILE_IMAGE_MOUNTER_E_SUCCESS,
+        InvalidArg = -1 => MOBILE_IMAGE_MOUNTER_E_INVALID_ARG,
+        PlistError = -2 => MOBILE_IMAGE_MOUNTER_E_PLIST_ERROR,
+        ConnFailed = -3 => MOBILE_IMAGE_MOUNTER_E_CONN_FAILED,
+        CommandFailed = -4 => MOBILE_IMAGE_MOUNTER_E_COMMAND_FAILED,
+        DeviceLocked = -5 => MOBILE_IMAGE_MOUNTER_E_DEVICE_LOCKED,
+        UnknownError = -256 => MOBILE_IMAGE_MOUNTER_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct mobile_image_mounter_client_private(c_void);
+pub type mobile_image_mounter_client_t = *mut mobile_image_mounter_client_private;
+
+/// Fills `buffer` with up to `length` bytes of the image, returning the number of bytes read, or
+/// a negative value on error.
+pub type mobile_image_mounter_upload_cb_t = unsafe extern "C" fn(buffer: *mut c_void, length: usize, user_data: *mut c_void) -> isize;
+
+extern "C" {
+    pub fn mobile_image_mounter_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut mobile_image_mounter_client_t) -> mobile_image_mounter_error_t;
+    pub fn mobile_image_mounter_start_service(device: idevice_t, client: *mut mobile_image_mounter_client_t, label: *const c_char) -> mobile_image_mounter_error_t;
+    pub fn mobile_image_mounter_free(client: mobile_image_mounter_client_t) -> mobile_image_mounter_error_t;
+
+    /// Looks up mounted images of a type such as `"Developer"`.
+    pub fn mobile_image_mounter_lookup_image(client: mobile_image_mounter_client_t, image_type: *const c_char, result: *mut plist_t) -> mobile_image_mounter_error_t;
+    pub fn mobile_image_mounter_upload_image(client: mobile_image_mounter_client_t, image_type: *const c_char, image_size: usize, signature: *const c_char, signature_size: u16, upload_cb: mobile_image_mounter_upload_cb_t, user_data: *mut c_void) -> mobile_image_mounter_error_t;
+    /// Mounts an image. `image_path` is `"/private/var/mobile/Media/PublicStaging/staging.dimage"`
+    /// for images uploaded with `mobile_image_mounter_upload_image`.
+    pub fn mobile_image_mounter_mount_image(client: mobile_image_mounter_client_t, image_path: *const c_char, signature: *const c_char, signature_size: u16, image_type: *const c_char, result: *mut plist_t) -> mobile_image_mounter_error_t;
+    pub fn mobile_image_mounter_hangup(client: mobile_image_mounter_client_t) -> mobile_image_mounter_error_t;
+}
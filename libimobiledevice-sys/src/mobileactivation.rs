@@ -0,0 +1,41 @@
+//! Bindings to `mobileactivation.h`.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_char, c_void};
+
+pub const MOBILEACTIVATION_SERVICE_NAME: &'static [u8] = b"com.apple.mobileactivationd\0";
+
+error_code! {
+    mobileactivation_error_t {
+        Success = 0 => MOBILEACTIVATION_E_SUCCESS,
+        InvalidArg = -1 => MOBILEACTIVATION_E_INVALID_ARG,
+        PlistError = -2 => MOBILEACTIVATION_E_PLIST_ERROR,
+        MuxError = -3 => MOBILEACTIVATION_E_MUX_ERROR,
+        UnknownRequest = -4 => MOBILEACTIVATION_E_UNKNOWN_REQUEST,
+        RequestFailed = -5 => MOBILEACTIVATION_E_REQUEST_FAILED,
+        UnknownError = -256 => MOBILEACTIVATION_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct mobileactivation_client_private(c_void);
+pub type mobileactivation_client_t = *mut mobileactivation_client_private;
+
+extern "C" {
+    pub fn mobileactivation_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut mobileactivation_client_t) -> mobileactivation_error_t;
+    pub fn mobileactivation_client_start_service(device: idevice_t, client: *mut mobileactivation_client_t, label: *const c_char) -> mobileactivation_error_t;
+    pub fn mobileactivation_client_free(client: mobileactivation_client_t) -> mobileactivation_error_t;
+
+    pub fn mobileactivation_get_activation_state(client: mobileactivation_client_t, state: *mut plist_t) -> mobileactivation_error_t;
+    /// Creates the session blob to send to Apple's activation server for a drm handshake.
+    pub fn mobileactivation_create_activation_session_info(client: mobileactivation_client_t, blob: *mut plist_t) -> mobileactivation_error_t;
+    pub fn mobileactivation_create_activation_info(client: mobileactivation_client_t, info: *mut plist_t) -> mobileactivation_error_t;
+    pub fn mobileactivation_create_activation_info_with_session(client: mobileactivation_client_t, handshake_response: plist_t, info: *mut plist_t) -> mobileactivation_error_t;
+    pub fn mobileactivation_activate(client: mobileactivation_client_t, activation_record: plist_t) -> mobileactivation_error_t;
+    pub fn mobileactivation_activate_with_session(client: mobileactivation_client_t, activation_record: plist_t, headers: plist_t) -> mobileactivation_error_t;
+    pub fn mobileactivation_deactivate(client: mobileactivation_client_t) -> mobileactivation_error_t;
+}
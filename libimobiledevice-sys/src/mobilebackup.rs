@@ -0,0 +1,64 @@
+//! Bindings to `mobilebackup.h`, the backup protocol of devices before iOS 4.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_char, c_uint, c_void};
+
+pub const MOBILEBACKUP_SERVICE_NAME: &'static [u8] = b"com.apple.mobilebackup\0";
+
+error_code! {
+    mobilebackup_error_t {
+        Success = 0 => MOBILEBACKUP_E_SUCCESS,
+        InvalidArg = -1 => MOBILEBACKUP_E_INVALID_ARG,
+        PlistError = -2 => MOBILEBACKUP_E_PLIST_ERROR,
+        MuxError = -3 => MOBILEBACKUP_E_MUX_ERROR,
+        // libimobiledevice 1.3 renumbered the codes below to make room for these two.
+        #[cfg(libimobiledevice_1_3)]
+        SslError = -4 => MOBILEBACKUP_E_SSL_ERROR,
+        #[cfg(libimobiledevice_1_3)]
+        ReceiveTimeout = -5 => MOBILEBACKUP_E_RECEIVE_TIMEOUT,
+        #[cfg(not(libimobiledevice_1_3))]
+        BadVersion = -4 => MOBILEBACKUP_E_BAD_VERSION,
+        #[cfg(not(libimobiledevice_1_3))]
+        ReplyNotOk = -5 => MOBILEBACKUP_E_REPLY_NOT_OK,
+        #[cfg(libimobiledevice_1_3)]
+        BadVersion = -6 => MOBILEBACKUP_E_BAD_VERSION,
+        #[cfg(libimobiledevice_1_3)]
+        ReplyNotOk = -7 => MOBILEBACKUP_E_REPLY_NOT_OK,
+        UnknownError = -256 => MOBILEBACKUP_E_UNKNOWN_ERROR,
+    }
+}
+
+pub type mobilebackup_flags_t = c_uint;
+pub const MB_RESTORE_NOTIFY_SPRINGBOARD: mobilebackup_flags_t = 1 << 0;
+pub const MB_RESTORE_PRESERVE_SETTINGS: mobilebackup_flags_t = 1 << 1;
+pub const MB_RESTORE_PRESERVE_CAMERA_ROLL: mobilebackup_flags_t = 1 << 2;
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct mobilebackup_client_private(c_void);
+pub type mobilebackup_client_t = *mut mobilebackup_client_private;
+
+extern "C" {
+    pub fn mobilebackup_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut mobilebackup_client_t) -> mobilebackup_error_t;
+    pub fn mobilebackup_client_start_service(device: idevice_t, client: *mut mobilebackup_client_t, label: *const c_char) -> mobilebackup_error_t;
+    pub fn mobilebackup_client_free(client: mobilebackup_client_t) -> mobilebackup_error_t;
+
+    pub fn mobilebackup_receive(client: mobilebackup_client_t, plist: *mut plist_t) -> mobilebackup_error_t;
+    pub fn mobilebackup_send(client: mobilebackup_client_t, plist: plist_t) -> mobilebackup_error_t;
+
+    /// Starts a backup. `backup_manifest` is the manifest of the previous backup, or NULL for a
+    /// full backup.
+    pub fn mobilebackup_request_backup(client: mobilebackup_client_t, backup_manifest: plist_t, base_path: *const c_char, proto_version: *const c_char) -> mobilebackup_error_t;
+    /// Acknowledges a `DLSendFile` message.
+    pub fn mobilebackup_send_backup_file_received(client: mobilebackup_client_t) -> mobilebackup_error_t;
+
+    pub fn mobilebackup_request_restore(client: mobilebackup_client_t, backup_manifest: plist_t, flags: mobilebackup_flags_t, proto_version: *const c_char) -> mobilebackup_error_t;
+    pub fn mobilebackup_receive_restore_file_received(client: mobilebackup_client_t, result: *mut plist_t) -> mobilebackup_error_t;
+    pub fn mobilebackup_receive_restore_application_received(client: mobilebackup_client_t, result: *mut plist_t) -> mobilebackup_error_t;
+    pub fn mobilebackup_send_restore_complete(client: mobilebackup_client_t) -> mobilebackup_error_t;
+
+    pub fn mobilebackup_send_error(client: mobilebackup_client_t, reason: *const c_char) -> mobilebackup_error_t;
+}
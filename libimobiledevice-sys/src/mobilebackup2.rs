@@ -0,0 +1,61 @@
+//! Bindings to `mobilebackup2.h`.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_char, c_double, c_int, c_void};
+
+pub const MOBILEBACKUP2_SERVICE_NAME: &'static [u8] = b"com.apple.mobilebackup2\0";
+
+error_code! {
+    mobilebackup2_error_t {
+        Success = 0 => MOBILEBACKUP2_E_SUCCESS,
+        InvalidArg = -1 => MOBILEBACKUP2_E_INVALID_ARG,
+        PlistError = -2 => MOBILEBACKUP2_E_PLIST_ERROR,
+        MuxError = -3 => MOBILEBACKUP2_E_MUX_ERROR,
+        // libimobiledevice 1.3 renumbered the codes below to make room for these two.
+        #[cfg(libimobiledevice_1_3)]
+        SslError = -4 => MOBILEBACKUP2_E_SSL_ERROR,
+        #[cfg(libimobiledevice_1_3)]
+        ReceiveTimeout = -5 => MOBILEBACKUP2_E_RECEIVE_TIMEOUT,
+        #[cfg(not(libimobiledevice_1_3))]
+        BadVersion = -4 => MOBILEBACKUP2_E_BAD_VERSION,
+        #[cfg(not(libimobiledevice_1_3))]
+        ReplyNotOk = -5 => MOBILEBACKUP2_E_REPLY_NOT_OK,
+        #[cfg(not(libimobiledevice_1_3))]
+        NoCommonVersion = -6 => MOBILEBACKUP2_E_NO_COMMON_VERSION,
+        #[cfg(libimobiledevice_1_3)]
+        BadVersion = -6 => MOBILEBACKUP2_E_BAD_VERSION,
+        #[cfg(libimobiledevice_1_3)]
+        ReplyNotOk = -7 => MOBILEBACKUP2_E_REPLY_NOT_OK,
+        #[cfg(libimobiledevice_1_3)]
+        NoCommonVersion = -8 => MOBILEBACKUP2_E_NO_COMMON_VERSION,
+        UnknownError = -256 => MOBILEBACKUP2_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct mobilebackup2_client_private(c_void);
+pub type mobilebackup2_client_t = *mut mobilebackup2_client_private;
+
+extern "C" {
+    pub fn mobilebackup2_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut mobilebackup2_client_t) -> mobilebackup2_error_t;
+    pub fn mobilebackup2_client_start_service(device: idevice_t, client: *mut mobilebackup2_client_t, label: *const c_char) -> mobilebackup2_error_t;
+    pub fn mobilebackup2_client_free(client: mobilebackup2_client_t) -> mobilebackup2_error_t;
+
+    /// Sends a `DLMessage*` device link message, or a plain plist if `message` is NULL.
+    pub fn mobilebackup2_send_message(client: mobilebackup2_client_t, message: *const c_char, options: plist_t) -> mobilebackup2_error_t;
+    /// Receives a device link message. The name of the `DLMessage*` is stored in `dlmessage`, to
+    /// be released with `free()`.
+    pub fn mobilebackup2_receive_message(client: mobilebackup2_client_t, msg_plist: *mut plist_t, dlmessage: *mut *mut c_char) -> mobilebackup2_error_t;
+    pub fn mobilebackup2_send_raw(client: mobilebackup2_client_t, data: *const c_char, length: u32, bytes: *mut u32) -> mobilebackup2_error_t;
+    pub fn mobilebackup2_receive_raw(client: mobilebackup2_client_t, data: *mut c_char, length: u32, bytes: *mut u32) -> mobilebackup2_error_t;
+
+    /// Negotiates the protocol version. `count` is the length of `local_versions`.
+    pub fn mobilebackup2_version_exchange(client: mobilebackup2_client_t, local_versions: *mut c_double, count: c_char, remote_version: *mut c_double) -> mobilebackup2_error_t;
+    /// Sends a request such as `"Backup"` or `"Restore"`.
+    pub fn mobilebackup2_send_request(client: mobilebackup2_client_t, request: *const c_char, target_identifier: *const c_char, source_identifier: *const c_char, options: plist_t) -> mobilebackup2_error_t;
+    pub fn mobilebackup2_send_status_response(client: mobilebackup2_client_t, status_code: c_int, status1: *const c_char, status2: plist_t) -> mobilebackup2_error_t;
+}
@@ -0,0 +1,98 @@
+//! Bindings to `mobilesync.h`.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_char, c_uint, c_void};
+
+pub const MOBILESYNC_SERVICE_NAME: &'static [u8] = b"com.apple.mobilesync\0";
+
+error_code! {
+    mobilesync_error_t {
+        Success = 0 => MOBILESYNC_E_SUCCESS,
+        InvalidArg = -1 => MOBILESYNC_E_INVALID_ARG,
+        PlistError = -2 => MOBILESYNC_E_PLIST_ERROR,
+        MuxError = -3 => MOBILESYNC_E_MUX_ERROR,
+        // libimobiledevice 1.3 renumbered the codes below to make room for these two.
+        #[cfg(libimobiledevice_1_3)]
+        SslError = -4 => MOBILESYNC_E_SSL_ERROR,
+        #[cfg(libimobiledevice_1_3)]
+        ReceiveTimeout = -5 => MOBILESYNC_E_RECEIVE_TIMEOUT,
+        #[cfg(not(libimobiledevice_1_3))]
+        BadVersion = -4 => MOBILESYNC_E_BAD_VERSION,
+        #[cfg(not(libimobiledevice_1_3))]
+        SyncRefused = -5 => MOBILESYNC_E_SYNC_REFUSED,
+        #[cfg(not(libimobiledevice_1_3))]
+        Cancelled = -6 => MOBILESYNC_E_CANCELLED,
+        #[cfg(not(libimobiledevice_1_3))]
+        WrongDirection = -7 => MOBILESYNC_E_WRONG_DIRECTION,
+        #[cfg(not(libimobiledevice_1_3))]
+        NotReady = -8 => MOBILESYNC_E_NOT_READY,
+        #[cfg(libimobiledevice_1_3)]
+        BadVersion = -6 => MOBILESYNC_E_BAD_VERSION,
+        #[cfg(libimobiledevice_1_3)]
+        SyncRefused = -7 => MOBILESYNC_E_SYNC_REFUSED,
+        #[cfg(libimobiledevice_1_3)]
+        Cancelled = -8 => MOBILESYNC_E_CANCELLED,
+        #[cfg(libimobiledevice_1_3)]
+        WrongDirection = -9 => MOBILESYNC_E_WRONG_DIRECTION,
+        #[cfg(libimobiledevice_1_3)]
+        NotReady = -10 => MOBILESYNC_E_NOT_READY,
+        UnknownError = -256 => MOBILESYNC_E_UNKNOWN_ERROR,
+    }
+}
+
+pub type mobilesync_sync_type_t = c_uint;
+/// Only the changes since the last sync are exchanged.
+pub const MOBILESYNC_SYNC_TYPE_FAST: mobilesync_sync_type_t = 0;
+/// All records are exchanged and compared.
+pub const MOBILESYNC_SYNC_TYPE_SLOW: mobilesync_sync_type_t = 1;
+/// The device replaces all of its records with those from the host.
+pub const MOBILESYNC_SYNC_TYPE_RESET: mobilesync_sync_type_t = 2;
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct mobilesync_client_private(c_void);
+pub type mobilesync_client_t = *mut mobilesync_client_private;
+
+#[repr(C)]
+pub struct mobilesync_anchors {
+    pub device_anchor: *mut c_char,
+    pub computer_anchor: *mut c_char,
+}
+pub type mobilesync_anchors_t = *mut mobilesync_anchors;
+
+extern "C" {
+    pub fn mobilesync_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut mobilesync_client_t) -> mobilesync_error_t;
+    pub fn mobilesync_client_start_service(device: idevice_t, client: *mut mobilesync_client_t, label: *const c_char) -> mobilesync_error_t;
+    pub fn mobilesync_client_free(client: mobilesync_client_t) -> mobilesync_error_t;
+
+    pub fn mobilesync_receive(client: mobilesync_client_t, plist: *mut plist_t) -> mobilesync_error_t;
+    pub fn mobilesync_send(client: mobilesync_client_t, plist: plist_t) -> mobilesync_error_t;
+
+    /// Starts syncing a data class such as `"com.apple.Contacts"`. If the device refuses, the
+    /// reason is stored in `error_description`, to be released with `free()`.
+    pub fn mobilesync_start(client: mobilesync_client_t, data_class: *const c_char, anchors: mobilesync_anchors_t, computer_data_class_version: u64, sync_type: *mut mobilesync_sync_type_t, device_data_class_version: *mut u64, error_description: *mut *mut c_char) -> mobilesync_error_t;
+    pub fn mobilesync_cancel(client: mobilesync_client_t, reason: *const c_char) -> mobilesync_error_t;
+    pub fn mobilesync_finish(client: mobilesync_client_t) -> mobilesync_error_t;
+
+    pub fn mobilesync_get_all_records_from_device(client: mobilesync_client_t) -> mobilesync_error_t;
+    pub fn mobilesync_get_changes_from_device(client: mobilesync_client_t) -> mobilesync_error_t;
+    pub fn mobilesync_clear_all_records_on_device(client: mobilesync_client_t) -> mobilesync_error_t;
+    pub fn mobilesync_receive_changes(client: mobilesync_client_t, entities: *mut plist_t, is_last_record: *mut u8, actions: *mut plist_t) -> mobilesync_error_t;
+    pub fn mobilesync_acknowledge_changes_from_device(client: mobilesync_client_t) -> mobilesync_error_t;
+
+    pub fn mobilesync_ready_to_send_changes_from_computer(client: mobilesync_client_t) -> mobilesync_error_t;
+    pub fn mobilesync_send_changes(client: mobilesync_client_t, entities: plist_t, is_last_record: u8, actions: plist_t) -> mobilesync_error_t;
+    /// Receives the new identifiers the device assigned to the records sent by the host.
+    pub fn mobilesync_remap_identifiers(client: mobilesync_client_t, mapping: *mut plist_t) -> mobilesync_error_t;
+
+    pub fn mobilesync_anchors_new(device_anchor: *const c_char, computer_anchor: *const c_char) -> mobilesync_anchors_t;
+    pub fn mobilesync_anchors_free(anchors: mobilesync_anchors_t);
+
+    pub fn mobilesync_actions_new() -> plist_t;
+    /// Adds key-value pairs to the actions, terminated by a NULL key.
+    pub fn mobilesync_actions_add(actions: plist_t, ...);
+    pub fn mobilesync_actions_free(actions: plist_t);
+}
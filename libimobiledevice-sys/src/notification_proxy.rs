@@ -0,0 +1,69 @@
+//! Bindings to `notification_proxy.h`.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+
+use std::os::raw::{c_char, c_void};
+
+pub const NP_SERVICE_NAME: &'static [u8] = b"com.apple.mobile.notification_proxy\0";
+
+error_code! {
+    np_error_t {
+        Success = 0 => NP_E_SUCCESS,
+        InvalidArg = -1 => NP_E_INVALID_ARG,
+        PlistError = -2 => NP_E_PLIST_ERROR,
+        ConnFailed = -3 => NP_E_CONN_FAILED,
+        UnknownError = -256 => NP_E_UNKNOWN_ERROR,
+    }
+}
+
+// Notifications posted by the host.
+pub const NP_SYNC_WILL_START: &'static [u8] = b"com.apple.itunes-mobdev.syncWillStart\0";
+pub const NP_SYNC_DID_START: &'static [u8] = b"com.apple.itunes-mobdev.syncDidStart\0";
+pub const NP_SYNC_DID_FINISH: &'static [u8] = b"com.apple.itunes-mobdev.syncDidFinish\0";
+pub const NP_SYNC_LOCK_REQUEST: &'static [u8] = b"com.apple.itunes-mobdev.syncLockRequest\0";
+
+// Notifications observed from the device.
+pub const NP_SYNC_CANCEL_REQUEST: &'static [u8] = b"com.apple.itunes-client.syncCancelRequest\0";
+pub const NP_SYNC_SUSPEND_REQUEST: &'static [u8] = b"com.apple.itunes-client.syncSuspendRequest\0";
+pub const NP_SYNC_RESUME_REQUEST: &'static [u8] = b"com.apple.itunes-client.syncResumeRequest\0";
+pub const NP_PHONE_NUMBER_CHANGED: &'static [u8] = b"com.apple.mobile.lockdown.phone_number_changed\0";
+pub const NP_DEVICE_NAME_CHANGED: &'static [u8] = b"com.apple.mobile.lockdown.device_name_changed\0";
+pub const NP_TIMEZONE_CHANGED: &'static [u8] = b"com.apple.mobile.lockdown.timezone_changed\0";
+pub const NP_TRUSTED_HOST_ATTACHED: &'static [u8] = b"com.apple.mobile.lockdown.trusted_host_attached\0";
+pub const NP_HOST_DETACHED: &'static [u8] = b"com.apple.mobile.lockdown.host_detached\0";
+pub const NP_HOST_ATTACHED: &'static [u8] = b"com.apple.mobile.lockdown.host_attached\0";
+pub const NP_REGISTRATION_FAILED: &'static [u8] = b"com.apple.mobile.lockdown.registration_failed\0";
+pub const NP_ACTIVATION_STATE: &'static [u8] = b"com.apple.mobile.lockdown.activation_state\0";
+pub const NP_BRICK_STATE: &'static [u8] = b"com.apple.mobile.lockdown.brick_state\0";
+pub const NP_DISK_USAGE_CHANGED: &'static [u8] = b"com.apple.mobile.lockdown.disk_usage_changed\0";
+pub const NP_DS_DOMAIN_CHANGED: &'static [u8] = b"com.apple.mobile.data_sync.domain_changed\0";
+pub const NP_BACKUP_DOMAIN_CHANGED: &'static [u8] = b"com.apple.mobile.backup.domain_changed\0";
+pub const NP_APP_INSTALLED: &'static [u8] = b"com.apple.mobile.application_installed\0";
+pub const NP_APP_UNINSTALLED: &'static [u8] = b"com.apple.mobile.application_uninstalled\0";
+pub const NP_DEV_IMAGE_MOUNTED: &'static [u8] = b"com.apple.mobile.developer_image_mounted\0";
+pub const NP_ATTEMPTACTIVATION: &'static [u8] = b"com.apple.springboard.attemptactivation\0";
+pub const NP_ITDBPREP_DID_END: &'static [u8] = b"com.apple.itdbprep.notification.didEnd\0";
+pub const NP_LANGUAGE_CHANGED: &'static [u8] = b"com.apple.language.changed\0";
+pub const NP_ADDRESS_BOOK_PREF_CHANGED: &'static [u8] = b"com.apple.AddressBook.PreferenceChanged\0";
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct np_client_private(c_void);
+pub type np_client_t = *mut np_client_private;
+
+/// Receives the name of an observed notification, from a background thread.
+pub type np_notify_cb_t = Option<unsafe extern "C" fn(notification: *const c_char, user_data: *mut c_void)>;
+
+extern "C" {
+    pub fn np_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut np_client_t) -> np_error_t;
+    pub fn np_client_start_service(device: idevice_t, client: *mut np_client_t, label: *const c_char) -> np_error_t;
+    pub fn np_client_free(client: np_client_t) -> np_error_t;
+
+    pub fn np_post_notification(client: np_client_t, notification: *const c_char) -> np_error_t;
+    pub fn np_observe_notification(client: np_client_t, notification: *const c_char) -> np_error_t;
+    /// Observes every notification in a NULL-terminated array.
+    pub fn np_observe_notifications(client: np_client_t, notification_spec: *mut *const c_char) -> np_error_t;
+    /// Sets the callback for observed notifications, or stops receiving them if `None`.
+    pub fn np_set_notify_callback(client: np_client_t, notify_cb: np_notify_cb_t, user_data: *mut c_void) -> np_error_t;
+}
@@ -0,0 +1,54 @@
+//! Bindings to `ostrace.h`, the unified logging relay which replaces `syslog_relay` on newer
+//! iOS versions.
+//!
+//! This service is newer than libimobiledevice 1.3.0, and needs a libimobiledevice built from a
+//! later source tree even though the feature only checks for 1.3.0.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_char, c_int, c_void};
+
+pub const OSTRACE_SERVICE_NAME: &'static [u8] = b"com.apple.os_trace_relay\0";
+
+error_code! {
+    ostrace_error_t {
+        Success = 0 => OSTRACE_E_SUCCESS,
+        InvalidArg = -1 => OSTRACE_E_INVALID_ARG,
+        PlistError = -2 => OSTRACE_E_PLIST_ERROR,
+        MuxError = -3 => OSTRACE_E_MUX_ERROR,
+        SslError = -4 => OSTRACE_E_SSL_ERROR,
+        NotEnoughData = -5 => OSTRACE_E_NOT_ENOUGH_DATA,
+        Timeout = -6 => OSTRACE_E_TIMEOUT,
+        RequestFailed = -7 => OSTRACE_E_REQUEST_FAILED,
+        UnknownError = -256 => OSTRACE_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct ostrace_client_private(c_void);
+pub type ostrace_client_t = *mut ostrace_client_private;
+
+/// Receives each log entry, from a background thread. An entry starts with a packed
+/// `ostrace_packet_header_t` (see the C header), followed by the strings it refers to.
+pub type ostrace_activity_cb_t = unsafe extern "C" fn(buf: *const c_void, len: usize, user_data: *mut c_void);
+/// Receives the next chunk of a log archive. Returns a negative value to abort.
+pub type ostrace_archive_write_cb_t = unsafe extern "C" fn(buf: *const c_void, len: usize, user_data: *mut c_void) -> c_int;
+
+extern "C" {
+    pub fn ostrace_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut ostrace_client_t) -> ostrace_error_t;
+    pub fn ostrace_client_start_service(device: idevice_t, client: *mut ostrace_client_t, label: *const c_char) -> ostrace_error_t;
+    pub fn ostrace_client_free(client: ostrace_client_t) -> ostrace_error_t;
+
+    /// Gets a dictionary of the running processes, keyed by pid, to choose a filter from.
+    pub fn ostrace_get_pid_list(client: ostrace_client_t, list: *mut plist_t) -> ostrace_error_t;
+    /// Starts capturing log entries. `options` may be NULL, or a dictionary with keys such as
+    /// `"Pid"` (-1 for all processes) and `"MessageFilter"`.
+    pub fn ostrace_start_activity(client: ostrace_client_t, options: plist_t, callback: ostrace_activity_cb_t, user_data: *mut c_void) -> ostrace_error_t;
+    pub fn ostrace_stop_activity(client: ostrace_client_t) -> ostrace_error_t;
+    /// Streams a `.logarchive` as a tar file. `options` may be NULL, or a dictionary with keys
+    /// such as `"StartTime"` or `"SizeLimit"`.
+    pub fn ostrace_create_archive(client: ostrace_client_t, options: plist_t, callback: ostrace_archive_write_cb_t, user_data: *mut c_void) -> ostrace_error_t;
+}
@@ -0,0 +1,46 @@
+//! Bindings to `preboard.h`.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_char, c_void};
+
+pub const PREBOARD_SERVICE_NAME: &'static [u8] = b"com.apple.preboardservice_v2\0";
+
+error_code! {
+    preboard_error_t {
+        Success = 0 => PREBOARD_E_SUCCESS,
+        InvalidArg = -1 => PREBOARD_E_INVALID_ARG,
+        PlistError = -2 => PREBOARD_E_PLIST_ERROR,
+        MuxError = -3 => PREBOARD_E_MUX_ERROR,
+        SslError = -4 => PREBOARD_E_SSL_ERROR,
+        NotEnoughData = -5 => PREBOARD_E_NOT_ENOUGH_DATA,
+        Timeout = -6 => PREBOARD_E_TIMEOUT,
+        OpInProgress = -10 => PREBOARD_E_OP_IN_PROGRESS,
+        UnknownError = -256 => PREBOARD_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct preboard_client_private(c_void);
+pub type preboard_client_t = *mut preboard_client_private;
+
+/// Receives the messages of a stashbag operation, from a background thread. `message` is NULL if
+/// receiving failed. Passing `None` to an operation makes it synchronous.
+pub type preboard_status_cb_t = Option<unsafe extern "C" fn(message: plist_t, user_data: *mut c_void)>;
+
+extern "C" {
+    pub fn preboard_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut preboard_client_t) -> preboard_error_t;
+    pub fn preboard_client_start_service(device: idevice_t, client: *mut preboard_client_t, label: *const c_char) -> preboard_error_t;
+    pub fn preboard_client_free(client: preboard_client_t) -> preboard_error_t;
+
+    pub fn preboard_send(client: preboard_client_t, plist: plist_t) -> preboard_error_t;
+    pub fn preboard_receive_with_timeout(client: preboard_client_t, plist: *mut plist_t, timeout_ms: u32) -> preboard_error_t;
+    pub fn preboard_receive(client: preboard_client_t, plist: *mut plist_t) -> preboard_error_t;
+
+    /// Asks the device to create a stashbag, which may require the user to enter the passcode.
+    pub fn preboard_create_stashbag(client: preboard_client_t, manifest: plist_t, status_cb: preboard_status_cb_t, user_data: *mut c_void) -> preboard_error_t;
+    pub fn preboard_commit_stashbag(client: preboard_client_t, manifest: plist_t, status_cb: preboard_status_cb_t, user_data: *mut c_void) -> preboard_error_t;
+}
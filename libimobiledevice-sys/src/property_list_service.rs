@@ -0,0 +1,39 @@
+//! Bindings to `property_list_service.h`, the plist framing spoken by most lockdown services.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_uint, c_void};
+
+error_code! {
+    property_list_service_error_t {
+        Success = 0 => PROPERTY_LIST_SERVICE_E_SUCCESS,
+        InvalidArg = -1 => PROPERTY_LIST_SERVICE_E_INVALID_ARG,
+        PlistError = -2 => PROPERTY_LIST_SERVICE_E_PLIST_ERROR,
+        MuxError = -3 => PROPERTY_LIST_SERVICE_E_MUX_ERROR,
+        SslError = -4 => PROPERTY_LIST_SERVICE_E_SSL_ERROR,
+        ReceiveTimeout = -5 => PROPERTY_LIST_SERVICE_E_RECEIVE_TIMEOUT,
+        NotEnoughData = -6 => PROPERTY_LIST_SERVICE_E_NOT_ENOUGH_DATA,
+        UnknownError = -256 => PROPERTY_LIST_SERVICE_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct property_list_service_client_private(c_void);
+pub type property_list_service_client_t = *mut property_list_service_client_private;
+
+extern "C" {
+    pub fn property_list_service_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut property_list_service_client_t) -> property_list_service_error_t;
+    pub fn property_list_service_client_free(client: property_list_service_client_t) -> property_list_service_error_t;
+
+    pub fn property_list_service_send_xml_plist(client: property_list_service_client_t, plist: plist_t) -> property_list_service_error_t;
+    pub fn property_list_service_send_binary_plist(client: property_list_service_client_t, plist: plist_t) -> property_list_service_error_t;
+    /// Receives a plist in either format. `timeout` is in milliseconds.
+    pub fn property_list_service_receive_plist_with_timeout(client: property_list_service_client_t, plist: *mut plist_t, timeout: c_uint) -> property_list_service_error_t;
+    pub fn property_list_service_receive_plist(client: property_list_service_client_t, plist: *mut plist_t) -> property_list_service_error_t;
+
+    pub fn property_list_service_enable_ssl(client: property_list_service_client_t) -> property_list_service_error_t;
+    pub fn property_list_service_disable_ssl(client: property_list_service_client_t) -> property_list_service_error_t;
+}
@@ -0,0 +1,62 @@
+//! Bindings to `restore.h`, for devices in restore mode.
+
+use idevice::idevice_t;
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_char, c_void};
+
+error_code! {
+    restored_error_t {
+        Success = 0 => RESTORE_E_SUCCESS,
+        InvalidArg = -1 => RESTORE_E_INVALID_ARG,
+        // libimobiledevice 1.3 dropped some codes and renumbered the rest.
+        #[cfg(not(libimobiledevice_1_3))]
+        InvalidConf = -2 => RESTORE_E_INVALID_CONF,
+        #[cfg(not(libimobiledevice_1_3))]
+        PlistError = -3 => RESTORE_E_PLIST_ERROR,
+        #[cfg(not(libimobiledevice_1_3))]
+        DictError = -4 => RESTORE_E_DICT_ERROR,
+        #[cfg(not(libimobiledevice_1_3))]
+        NotEnoughData = -5 => RESTORE_E_NOT_ENOUGH_DATA,
+        #[cfg(not(libimobiledevice_1_3))]
+        MuxError = -6 => RESTORE_E_MUX_ERROR,
+        #[cfg(not(libimobiledevice_1_3))]
+        StartRestoreFailed = -7 => RESTORE_E_START_RESTORE_FAILED,
+        #[cfg(not(libimobiledevice_1_3))]
+        DeviceError = -8 => RESTORE_E_DEVICE_ERROR,
+        #[cfg(libimobiledevice_1_3)]
+        PlistError = -2 => RESTORE_E_PLIST_ERROR,
+        #[cfg(libimobiledevice_1_3)]
+        MuxError = -3 => RESTORE_E_MUX_ERROR,
+        #[cfg(libimobiledevice_1_3)]
+        NotEnoughData = -4 => RESTORE_E_NOT_ENOUGH_DATA,
+        #[cfg(libimobiledevice_1_3)]
+        ReceiveTimeout = -5 => RESTORE_E_RECEIVE_TIMEOUT,
+        UnknownError = -256 => RESTORE_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct restored_client_private(c_void);
+pub type restored_client_t = *mut restored_client_private;
+
+extern "C" {
+    pub fn restored_client_new(device: idevice_t, client: *mut restored_client_t, label: *const c_char) -> restored_error_t;
+    pub fn restored_client_free(client: restored_client_t) -> restored_error_t;
+    pub fn restored_client_set_label(client: restored_client_t, label: *const c_char);
+
+    /// Checks that the device is in restore mode. `type_` is set to `"com.apple.mobile.restored"`
+    /// and must be released with `free()`.
+    pub fn restored_query_type(client: restored_client_t, type_: *mut *mut c_char, version: *mut u64) -> restored_error_t;
+    pub fn restored_query_value(client: restored_client_t, key: *const c_char, value: *mut plist_t) -> restored_error_t;
+    /// Gets a value from the information returned by `restored_query_type`.
+    pub fn restored_get_value(client: restored_client_t, key: *const c_char, value: *mut plist_t) -> restored_error_t;
+
+    pub fn restored_send(client: restored_client_t, plist: plist_t) -> restored_error_t;
+    pub fn restored_receive(client: restored_client_t, plist: *mut plist_t) -> restored_error_t;
+    pub fn restored_goodbye(client: restored_client_t) -> restored_error_t;
+
+    pub fn restored_start_restore(client: restored_client_t, options: plist_t, version: u64) -> restored_error_t;
+    pub fn restored_reboot(client: restored_client_t) -> restored_error_t;
+}
@@ -0,0 +1,73 @@
+//! Bindings to `reverse_proxy.h`, letting the device open connections through the host.
+//!
+//! This service is newer than libimobiledevice 1.3.0, and needs a libimobiledevice built from a
+//! later source tree even though the feature only checks for 1.3.0.
+
+use idevice::idevice_t;
+
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+
+pub const REVERSE_PROXY_DEFAULT_PORT: u16 = 1082;
+
+error_code! {
+    reverse_proxy_error_t {
+        Success = 0 => REVERSE_PROXY_E_SUCCESS,
+        InvalidArg = -1 => REVERSE_PROXY_E_INVALID_ARG,
+        PlistError = -2 => REVERSE_PROXY_E_PLIST_ERROR,
+        MuxError = -3 => REVERSE_PROXY_E_MUX_ERROR,
+        SslError = -4 => REVERSE_PROXY_E_SSL_ERROR,
+        NotEnoughData = -5 => REVERSE_PROXY_E_NOT_ENOUGH_DATA,
+        Timeout = -6 => REVERSE_PROXY_E_TIMEOUT,
+        UnknownError = -256 => REVERSE_PROXY_E_UNKNOWN_ERROR,
+    }
+}
+
+pub type reverse_proxy_client_type_t = c_uint;
+/// The control connection, created by `reverse_proxy_client_create_with_*`.
+pub const RP_TYPE_CTRL: reverse_proxy_client_type_t = 1;
+/// A connection requested by the device, passed to the callbacks.
+pub const RP_TYPE_CONN: reverse_proxy_client_type_t = 2;
+
+pub type reverse_proxy_status_t = c_uint;
+pub const RP_STATUS_READY: reverse_proxy_status_t = 1;
+pub const RP_STATUS_TERMINATE: reverse_proxy_status_t = 2;
+pub const RP_STATUS_CONNECT_REQ: reverse_proxy_status_t = 3;
+pub const RP_STATUS_SHUTDOWN_REQ: reverse_proxy_status_t = 4;
+pub const RP_STATUS_CONNECTED: reverse_proxy_status_t = 5;
+pub const RP_STATUS_DISCONNECTED: reverse_proxy_status_t = 6;
+
+pub type reverse_proxy_data_direction_t = c_uint;
+/// Data sent from the device to the remote host.
+pub const RP_DATA_DIRECTION_OUT: reverse_proxy_data_direction_t = 1;
+/// Data received from the remote host for the device.
+pub const RP_DATA_DIRECTION_IN: reverse_proxy_data_direction_t = 2;
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct reverse_proxy_client_private(c_void);
+pub type reverse_proxy_client_t = *mut reverse_proxy_client_private;
+
+pub type reverse_proxy_log_cb_t = Option<unsafe extern "C" fn(client: reverse_proxy_client_t, log_msg: *const c_char, user_data: *mut c_void)>;
+pub type reverse_proxy_data_cb_t = Option<unsafe extern "C" fn(client: reverse_proxy_client_t, direction: reverse_proxy_data_direction_t, buffer: *const c_char, length: u32, user_data: *mut c_void)>;
+pub type reverse_proxy_status_cb_t = Option<unsafe extern "C" fn(client: reverse_proxy_client_t, status: reverse_proxy_status_t, status_msg: *const c_char, user_data: *mut c_void)>;
+
+extern "C" {
+    /// Creates the control connection through the `com.apple.PurpleReverseProxy.Ctrl` service,
+    /// as used by the normal OS.
+    pub fn reverse_proxy_client_create_with_service(device: idevice_t, client: *mut reverse_proxy_client_t, label: *const c_char) -> reverse_proxy_error_t;
+    /// Creates the control connection on a port, usually `REVERSE_PROXY_DEFAULT_PORT`, as used in
+    /// restore mode.
+    pub fn reverse_proxy_client_create_with_port(device: idevice_t, client: *mut reverse_proxy_client_t, device_port: u16) -> reverse_proxy_error_t;
+    pub fn reverse_proxy_client_free(client: reverse_proxy_client_t) -> reverse_proxy_error_t;
+    pub fn reverse_proxy_get_type(client: reverse_proxy_client_t) -> reverse_proxy_client_type_t;
+
+    /// Starts proxying connections in a background thread. `control_protocol_version` is 1 or 2.
+    pub fn reverse_proxy_client_start_proxy(client: reverse_proxy_client_t, control_protocol_version: c_int) -> reverse_proxy_error_t;
+    pub fn reverse_proxy_client_set_status_callback(client: reverse_proxy_client_t, callback: reverse_proxy_status_cb_t, user_data: *mut c_void);
+    pub fn reverse_proxy_client_set_log_callback(client: reverse_proxy_client_t, callback: reverse_proxy_log_cb_t, user_data: *mut c_void);
+    pub fn reverse_proxy_client_set_data_callback(client: reverse_proxy_client_t, callback: reverse_proxy_data_cb_t, user_data: *mut c_void);
+
+    pub fn reverse_proxy_client_send(client: reverse_proxy_client_t, data: *const c_char, len: u32, sent: *mut u32) -> reverse_proxy_error_t;
+    pub fn reverse_proxy_client_receive_with_timeout(client: reverse_proxy_client_t, buffer: *mut c_char, len: u32, received: *mut u32, timeout: c_uint) -> reverse_proxy_error_t;
+    pub fn reverse_proxy_client_receive(client: reverse_proxy_client_t, buffer: *mut c_char, len: u32, received: *mut u32) -> reverse_proxy_error_t;
+}
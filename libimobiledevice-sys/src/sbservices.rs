@@ -0,0 +1,45 @@
+//! Bindings to `sbservices.h`.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_char, c_uint, c_void};
+
+pub const SBSERVICES_SERVICE_NAME: &'static [u8] = b"com.apple.springboardservices\0";
+
+error_code! {
+    sbservices_error_t {
+        Success = 0 => SBSERVICES_E_SUCCESS,
+        InvalidArg = -1 => SBSERVICES_E_INVALID_ARG,
+        PlistError = -2 => SBSERVICES_E_PLIST_ERROR,
+        ConnFailed = -3 => SBSERVICES_E_CONN_FAILED,
+        UnknownError = -256 => SBSERVICES_E_UNKNOWN_ERROR,
+    }
+}
+
+pub type sbservices_interface_orientation_t = c_uint;
+pub const SBSERVICES_INTERFACE_ORIENTATION_UNKNOWN: sbservices_interface_orientation_t = 0;
+pub const SBSERVICES_INTERFACE_ORIENTATION_PORTRAIT: sbservices_interface_orientation_t = 1;
+pub const SBSERVICES_INTERFACE_ORIENTATION_PORTRAIT_UPSIDE_DOWN: sbservices_interface_orientation_t = 2;
+pub const SBSERVICES_INTERFACE_ORIENTATION_LANDSCAPE_RIGHT: sbservices_interface_orientation_t = 3;
+pub const SBSERVICES_INTERFACE_ORIENTATION_LANDSCAPE_LEFT: sbservices_interface_orientation_t = 4;
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct sbservices_client_private(c_void);
+pub type sbservices_client_t = *mut sbservices_client_private;
+
+extern "C" {
+    pub fn sbservices_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut sbservices_client_t) -> sbservices_error_t;
+    pub fn sbservices_client_start_service(device: idevice_t, client: *mut sbservices_client_t, label: *const c_char) -> sbservices_error_t;
+    pub fn sbservices_client_free(client: sbservices_client_t) -> sbservices_error_t;
+
+    /// Gets the home screen layout. `format_version` may be NULL, or `"2"` to include the icon
+    /// grid size.
+    pub fn sbservices_get_icon_state(client: sbservices_client_t, state: *mut plist_t, format_version: *const c_char) -> sbservices_error_t;
+    pub fn sbservices_set_icon_state(client: sbservices_client_t, newstate: plist_t) -> sbservices_error_t;
+    pub fn sbservices_get_icon_pngdata(client: sbservices_client_t, bundle_id: *const c_char, pngdata: *mut *mut c_char, pngsize: *mut u64) -> sbservices_error_t;
+    pub fn sbservices_get_interface_orientation(client: sbservices_client_t, interface_orientation: *mut sbservices_interface_orientation_t) -> sbservices_error_t;
+    pub fn sbservices_get_home_screen_wallpaper_pngdata(client: sbservices_client_t, pngdata: *mut *mut c_char, pngsize: *mut u64) -> sbservices_error_t;
+}
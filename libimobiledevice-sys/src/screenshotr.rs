@@ -0,0 +1,41 @@
+//! Bindings to `screenshotr.h`.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+
+use std::os::raw::{c_char, c_void};
+
+pub const SCREENSHOTR_SERVICE_NAME: &'static [u8] = b"com.apple.mobile.screenshotr\0";
+
+error_code! {
+    screenshotr_error_t {
+        Success = 0 => SCREENSHOTR_E_SUCCESS,
+        InvalidArg = -1 => SCREENSHOTR_E_INVALID_ARG,
+        PlistError = -2 => SCREENSHOTR_E_PLIST_ERROR,
+        MuxError = -3 => SCREENSHOTR_E_MUX_ERROR,
+        #[cfg(libimobiledevice_1_3)]
+        SslError = -4 => SCREENSHOTR_E_SSL_ERROR,
+        #[cfg(libimobiledevice_1_3)]
+        ReceiveTimeout = -5 => SCREENSHOTR_E_RECEIVE_TIMEOUT,
+        // libimobiledevice 1.3 renumbered this code to make room for the two above.
+        #[cfg(not(libimobiledevice_1_3))]
+        BadVersion = -4 => SCREENSHOTR_E_BAD_VERSION,
+        #[cfg(libimobiledevice_1_3)]
+        BadVersion = -6 => SCREENSHOTR_E_BAD_VERSION,
+        UnknownError = -256 => SCREENSHOTR_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct screenshotr_client_private(c_void);
+pub type screenshotr_client_t = *mut screenshotr_client_private;
+
+extern "C" {
+    pub fn screenshotr_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut screenshotr_client_t) -> screenshotr_error_t;
+    pub fn screenshotr_client_start_service(device: idevice_t, client: *mut screenshotr_client_t, label: *const c_char) -> screenshotr_error_t;
+    pub fn screenshotr_client_free(client: screenshotr_client_t) -> screenshotr_error_t;
+
+    /// Captures the screen as a TIFF (or PNG on newer devices) image, to be released with `free()`.
+    pub fn screenshotr_take_screenshot(client: screenshotr_client_t, imgdata: *mut *mut c_char, imgsize: *mut u64) -> screenshotr_error_t;
+}
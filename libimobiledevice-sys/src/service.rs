@@ -0,0 +1,44 @@
+//! Bindings to `service.h`, a raw connection to any lockdown service.
+
+use idevice::{idevice_t, idevice_connection_t};
+use lockdown::lockdownd_service_descriptor_t;
+
+use std::os::raw::{c_char, c_uint, c_void};
+
+error_code! {
+    service_error_t {
+        Success = 0 => SERVICE_E_SUCCESS,
+        InvalidArg = -1 => SERVICE_E_INVALID_ARG,
+        MuxError = -3 => SERVICE_E_MUX_ERROR,
+        SslError = -4 => SERVICE_E_SSL_ERROR,
+        StartServiceError = -5 => SERVICE_E_START_SERVICE_ERROR,
+        NotEnoughData = -6 => SERVICE_E_NOT_ENOUGH_DATA,
+        Timeout = -7 => SERVICE_E_TIMEOUT,
+        UnknownError = -256 => SERVICE_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct service_client_private(c_void);
+pub type service_client_t = *mut service_client_private;
+
+extern "C" {
+    pub fn service_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut service_client_t) -> service_error_t;
+    /// Starts a service through lockdownd and creates its client with `constructor_func`, e.g.
+    /// `afc_client_new` cast to the generic signature. The service's own error code is stored in
+    /// `error_code` if not NULL.
+    pub fn service_client_factory_start_service(device: idevice_t, service_name: *const c_char, client: *mut *mut c_void, label: *const c_char, constructor_func: Option<unsafe extern "C" fn(idevice_t, lockdownd_service_descriptor_t, *mut *mut c_void) -> i32>, error_code: *mut i32) -> service_error_t;
+    pub fn service_client_free(client: service_client_t) -> service_error_t;
+
+    pub fn service_send(client: service_client_t, data: *const c_char, size: u32, sent: *mut u32) -> service_error_t;
+    pub fn service_receive_with_timeout(client: service_client_t, data: *mut c_char, size: u32, received: *mut u32, timeout: c_uint) -> service_error_t;
+    pub fn service_receive(client: service_client_t, data: *mut c_char, size: u32, received: *mut u32) -> service_error_t;
+
+    pub fn service_enable_ssl(client: service_client_t) -> service_error_t;
+    pub fn service_disable_ssl(client: service_client_t) -> service_error_t;
+    /// Disables SSL, skipping the SSL shutdown if `ssl_bypass` is nonzero.
+    pub fn service_disable_bypass_ssl(client: service_client_t, ssl_bypass: u8) -> service_error_t;
+    /// Gets the underlying connection, which is still owned by the client.
+    pub fn service_get_connection(client: service_client_t, connection: *mut idevice_connection_t) -> service_error_t;
+}
@@ -0,0 +1,46 @@
+//! Bindings to `syslog_relay.h`.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+
+use std::os::raw::{c_char, c_uint, c_void};
+
+pub const SYSLOG_RELAY_SERVICE_NAME: &'static [u8] = b"com.apple.syslog_relay\0";
+
+error_code! {
+    syslog_relay_error_t {
+        Success = 0 => SYSLOG_RELAY_E_SUCCESS,
+        InvalidArg = -1 => SYSLOG_RELAY_E_INVALID_ARG,
+        MuxError = -2 => SYSLOG_RELAY_E_MUX_ERROR,
+        SslError = -3 => SYSLOG_RELAY_E_SSL_ERROR,
+        #[cfg(libimobiledevice_1_3)]
+        NotEnoughData = -4 => SYSLOG_RELAY_E_NOT_ENOUGH_DATA,
+        #[cfg(libimobiledevice_1_3)]
+        Timeout = -5 => SYSLOG_RELAY_E_TIMEOUT,
+        UnknownError = -256 => SYSLOG_RELAY_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct syslog_relay_client_private(c_void);
+pub type syslog_relay_client_t = *mut syslog_relay_client_private;
+
+/// Receives the captured log one character at a time, from a background thread.
+pub type syslog_relay_receive_cb_t = unsafe extern "C" fn(c: c_char, user_data: *mut c_void);
+
+extern "C" {
+    pub fn syslog_relay_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut syslog_relay_client_t) -> syslog_relay_error_t;
+    pub fn syslog_relay_client_start_service(device: idevice_t, client: *mut syslog_relay_client_t, label: *const c_char) -> syslog_relay_error_t;
+    pub fn syslog_relay_client_free(client: syslog_relay_client_t) -> syslog_relay_error_t;
+
+    /// Starts capturing the log, skipping the NUL separators and unescaping control characters.
+    pub fn syslog_relay_start_capture(client: syslog_relay_client_t, callback: syslog_relay_receive_cb_t, user_data: *mut c_void) -> syslog_relay_error_t;
+    /// Starts capturing the log, passing every byte through unchanged.
+    #[cfg(feature="libimobiledevice-1-3")]
+    pub fn syslog_relay_start_capture_raw(client: syslog_relay_client_t, callback: syslog_relay_receive_cb_t, user_data: *mut c_void) -> syslog_relay_error_t;
+    pub fn syslog_relay_stop_capture(client: syslog_relay_client_t) -> syslog_relay_error_t;
+
+    pub fn syslog_relay_receive_with_timeout(client: syslog_relay_client_t, data: *mut c_char, size: u32, received: *mut u32, timeout: c_uint) -> syslog_relay_error_t;
+    pub fn syslog_relay_receive(client: syslog_relay_client_t, data: *mut c_char, size: u32, received: *mut u32) -> syslog_relay_error_t;
+}
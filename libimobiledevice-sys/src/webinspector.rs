@@ -0,0 +1,37 @@
+//! Bindings to `webinspector.h`.
+
+use idevice::idevice_t;
+use lockdown::lockdownd_service_descriptor_t;
+use libplist_sys::plist_t;
+
+use std::os::raw::{c_char, c_void};
+
+pub const WEBINSPECTOR_SERVICE_NAME: &'static [u8] = b"com.apple.webinspector\0";
+
+error_code! {
+    webinspector_error_t {
+        Success = 0 => WEBINSPECTOR_E_SUCCESS,
+        InvalidArg = -1 => WEBINSPECTOR_E_INVALID_ARG,
+        PlistError = -2 => WEBINSPECTOR_E_PLIST_ERROR,
+        MuxError = -3 => WEBINSPECTOR_E_MUX_ERROR,
+        SslError = -4 => WEBINSPECTOR_E_SSL_ERROR,
+        NotEnoughData = -5 => WEBINSPECTOR_E_NOT_ENOUGH_DATA,
+        Timeout = -6 => WEBINSPECTOR_E_TIMEOUT,
+        UnknownError = -256 => WEBINSPECTOR_E_UNKNOWN_ERROR,
+    }
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct webinspector_client_private(c_void);
+pub type webinspector_client_t = *mut webinspector_client_private;
+
+extern "C" {
+    pub fn webinspector_client_new(device: idevice_t, service: lockdownd_service_descriptor_t, client: *mut webinspector_client_t) -> webinspector_error_t;
+    pub fn webinspector_client_start_service(device: idevice_t, client: *mut webinspector_client_t, label: *const c_char) -> webinspector_error_t;
+    pub fn webinspector_client_free(client: webinspector_client_t) -> webinspector_error_t;
+
+    pub fn webinspector_send(client: webinspector_client_t, plist: plist_t) -> webinspector_error_t;
+    pub fn webinspector_receive(client: webinspector_client_t, plist: *mut plist_t) -> webinspector_error_t;
+    pub fn webinspector_receive_with_timeout(client: webinspector_client_t, plist: *mut plist_t, timeout_ms: u32) -> webinspector_error_t;
+}
@@ -0,0 +1,85 @@
+//! AFC read/write throughput at several chunk sizes.
+//!
+//! These need a paired device, so they only run if `LIBIMOBILEDEVICE_BENCH_UDID` is set to its
+//! UDID (or to `any` for the first device found). A scratch file is written to the root of the
+//! media directory and removed afterwards.
+
+#[macro_use] extern crate criterion;
+extern crate libimobiledevice;
+extern crate libimobiledevice_sys;
+
+use criterion::{Criterion, BenchmarkId, Throughput};
+use libimobiledevice::{AfcClient, Device, LockdownClient};
+use libimobiledevice_sys::afc::{AFC_FOPEN_RDONLY, AFC_FOPEN_WRONLY};
+
+use std::env;
+use std::io::{Read, Write};
+
+const CHUNK_SIZES: &'static [usize] = &[4096, 65536, 1048576];
+const FILE_SIZE: usize = 4 * 1048576;
+const SCRATCH_PATH: &'static str = "/libimobiledevice-bench.tmp";
+
+fn connect() -> Option<AfcClient> {
+    let udid = match env::var("LIBIMOBILEDEVICE_BENCH_UDID") {
+        Ok(udid) => udid,
+        Err(_) => {
+            eprintln!("LIBIMOBILEDEVICE_BENCH_UDID is not set, skipping AFC benchmarks");
+            return None;
+        }
+    };
+    let device = if udid == "any" { Device::any() } else { Device::new(&udid) }.unwrap();
+    let lockdown = LockdownClient::new_with_handshake(&device, None).unwrap();
+    let service = lockdown.start_service("com.apple.afc").unwrap();
+    Some(AfcClient::new(&device, &service).unwrap())
+}
+
+fn write_file(client: &AfcClient, data: &[u8], chunk_size: usize) {
+    let mut file = client.open(SCRATCH_PATH, AFC_FOPEN_WRONLY).unwrap();
+    for chunk in data.chunks(chunk_size) {
+        file.write_all(chunk).unwrap();
+    }
+}
+
+fn read_file(client: &AfcClient, buf: &mut [u8]) -> usize {
+    let mut file = client.open(SCRATCH_PATH, AFC_FOPEN_RDONLY).unwrap();
+    let mut total = 0;
+    loop {
+        match file.read(buf).unwrap() {
+            0 => return total,
+            n => total += n,
+        }
+    }
+}
+
+fn bench_afc(c: &mut Criterion) {
+    let client = match connect() {
+        Some(client) => client,
+        None => return,
+    };
+    let data = vec![0x5a; FILE_SIZE];
+
+    let mut group = c.benchmark_group("afc_write");
+    group.throughput(Throughput::Bytes(FILE_SIZE as u64));
+    for &chunk_size in CHUNK_SIZES {
+        group.bench_with_input(BenchmarkId::from_parameter(chunk_size), &chunk_size, |b, &size| {
+            b.iter(|| write_file(&client, &data, size))
+        });
+    }
+    group.finish();
+
+    write_file(&client, &data, FILE_SIZE);
+    let mut group = c.benchmark_group("afc_read");
+    group.throughput(Throughput::Bytes(FILE_SIZE as u64));
+    for &chunk_size in CHUNK_SIZES {
+        let mut buf = vec![0; chunk_size];
+        group.bench_function(BenchmarkId::from_parameter(chunk_size), |b| {
+            b.iter(|| assert_eq!(read_file(&client, &mut buf), FILE_SIZE))
+        });
+    }
+    group.finish();
+
+    client.remove(SCRATCH_PATH).unwrap();
+}
+
+criterion_group!(benches, bench_afc);
+criterion_main!(benches);
@@ -0,0 +1,9 @@
+use std::env;
+
+fn main() {
+    // Mirror the ABI detected by libimobiledevice-sys, for the error codes renumbered in 1.3.
+    println!("cargo:rustc-check-cfg=cfg(libimobiledevice_1_3)");
+    if env::var("DEP_IMOBILEDEVICE_ABI").map(|abi| abi == "1.3").unwrap_or(false) {
+        println!("cargo:rustc-cfg=libimobiledevice_1_3");
+    }
+}
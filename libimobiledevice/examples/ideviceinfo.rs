@@ -0,0 +1,56 @@
+//! Lists connected devices, prints their standard properties and dumps their lockdown values.
+//!
+//! Usage: `cargo run --example ideviceinfo [domain...]`
+//!
+//! Without arguments the default domain is dumped, otherwise each named domain, e.g.
+//! `com.apple.disk_usage`.
+
+extern crate libimobiledevice;
+
+use libimobiledevice::{Device, DeviceInfo, Error, LockdownClient, devices};
+
+use std::env;
+use std::process::exit;
+
+fn dump_domain(client: &LockdownClient, domain: Option<&str>) {
+    match client.get_value(domain, None) {
+        Ok(node) => print!("{}", &*node.to_xml()),
+        Err(e) => println!("  (failed to read domain: {:?})", e),
+    }
+}
+
+fn show_device(udid: &str, domains: &[String]) -> Result<(), Error> {
+    let device = try!(Device::new(udid));
+    let client = try!(LockdownClient::new_with_handshake(&device, Some("ideviceinfo")));
+
+    let info = try!(DeviceInfo::query(&client));
+    println!("{} ({}, {} {} {})", info.device_name, info.product_type, info.device_class, info.product_version, info.build_version);
+
+    if domains.is_empty() {
+        dump_domain(&client, None);
+    } else {
+        for domain in domains {
+            println!("-- {}", domain);
+            dump_domain(&client, Some(domain));
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let domains = env::args().skip(1).collect::<Vec<_>>();
+    let udids = match devices() {
+        Ok(udids) => udids,
+        Err(e) => {
+            eprintln!("cannot list devices: {:?}", e);
+            exit(1);
+        }
+    };
+
+    for udid in udids {
+        println!("== {}", udid);
+        if let Err(e) = show_device(&udid, &domains) {
+            println!("  ({})", e);
+        }
+    }
+}
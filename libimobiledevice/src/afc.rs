@@ -0,0 +1,790 @@
+//! Client of the Apple File Conduit (AFC) service, which gives access to the media directory of a
+//! device.
+
+use libimobiledevice_sys::afc::*;
+use libplist::c_str::{ToCStr, from_c_str_lossy};
+use libc::{c_char, SEEK_SET, SEEK_CUR, SEEK_END};
+
+use std::borrow::Cow;
+use std::ffi::CStr;
+use std::fmt;
+use std::fs;
+use std::ops::{Deref, DerefMut};
+use std::io::{self, Read, Write, Seek, SeekFrom};
+use std::path::Path;
+use std::ptr::null_mut;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use device::Device;
+use lockdown::ServiceDescriptor;
+use internal::{ToResult, clamp_len, take_string};
+
+/// An owned AFC client. The client is freed when dropped.
+pub struct AfcClient(afc_client_t);
+
+unsafe impl Send for AfcClient {}
+
+/// Converts an AFC error code into an I/O error.
+pub fn to_io_error(error: afc_error_t) -> io::Error {
+    let kind = match error {
+        AFC_E_INVALID_ARG => io::ErrorKind::InvalidInput,
+        AFC_E_OBJECT_NOT_FOUND => io::ErrorKind::NotFound,
+        AFC_E_PERM_DENIED => io::ErrorKind::PermissionDenied,
+        AFC_E_OBJECT_EXISTS => io::ErrorKind::AlreadyExists,
+        AFC_E_OP_TIMEOUT => io::ErrorKind::TimedOut,
+        AFC_E_OP_WOULD_BLOCK => io::ErrorKind::WouldBlock,
+        AFC_E_OP_INTERRUPTED => io::ErrorKind::Interrupted,
+        AFC_E_NOT_ENOUGH_DATA => io::ErrorKind::UnexpectedEof,
+        AFC_E_SERVICE_NOT_CONNECTED => io::ErrorKind::NotConnected,
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, format!("AFC error: {:?}", error))
+}
+
+/// Converts a path argument into a C string, rejecting interior NULs.
+fn path_to_c_str(path: &str) -> Result<Cow<CStr>, afc_error_t> {
+    path.to_c_str().map_err(|_| AFC_E_INVALID_ARG)
+}
+
+impl AfcClient {
+    pub unsafe fn from_ptr(client: afc_client_t) -> AfcClient {
+        AfcClient(client)
+    }
+
+    pub fn as_ptr(&self) -> afc_client_t {
+        self.0
+    }
+
+    /// Connects to an AFC service started by `LockdownClient::start_service`.
+    pub fn new(device: &Device, service: &ServiceDescriptor) -> Result<AfcClient, afc_error_t> {
+        let mut client = null_mut();
+        unsafe {
+            try!(afc_client_new(device.as_ptr(), service.as_ptr(), &mut client).to_result());
+            Ok(AfcClient::from_ptr(client))
+        }
+    }
+
+    /// Reads the file system information of the device.
+    pub fn device_info(&self) -> Result<AfcDeviceInfo, afc_error_t> {
+        let mut list = null_mut();
+        try!(unsafe { afc_get_device_info(self.0, &mut list) }.to_result());
+        Ok(AfcDeviceInfo::from_pairs(&StringList(list).pairs()))
+    }
+
+    /// Reads a single raw value of the device information, e.g. `FSFreeBytes`.
+    pub fn device_info_key(&self, key: &str) -> Result<String, afc_error_t> {
+        let key = try!(path_to_c_str(key));
+        let mut value = null_mut();
+        unsafe {
+            try!(afc_get_device_info_key(self.0, key.as_ptr(), &mut value).to_result());
+            if value.is_null() {
+                return Err(AFC_E_OBJECT_NOT_FOUND);
+            }
+            Ok(take_string(value))
+        }
+    }
+
+    /// Lists a directory on the device. The `.` and `..` entries are skipped.
+    pub fn read_dir(&self, path: &str) -> Result<ReadDir, afc_error_t> {
+        let c_path = try!(path_to_c_str(path));
+        let mut list = null_mut();
+        try!(unsafe { afc_read_directory(self.0, c_path.as_ptr(), &mut list) }.to_result());
+        Ok(ReadDir {
+            client: self,
+            dir: path.trim_end_matches('/').to_owned(),
+            list: StringList(list),
+            index: 0,
+        })
+    }
+
+    /// Reads the raw file information of a path, as key/value pairs like `("st_size", "1024")`.
+    pub fn file_info(&self, path: &str) -> Result<Vec<(String, String)>, afc_error_t> {
+        let path = try!(path_to_c_str(path));
+        let mut list = null_mut();
+        try!(unsafe { afc_get_file_info(self.0, path.as_ptr(), &mut list) }.to_result());
+        Ok(StringList(list).pairs())
+    }
+
+    /// Reads the metadata of a path. Symbolic links are not followed.
+    pub fn metadata(&self, path: &str) -> Result<Metadata, afc_error_t> {
+        self.file_info(path).map(|info| Metadata::from_file_info(&info))
+    }
+
+    /// Creates a directory on the device.
+    pub fn create_dir(&self, path: &str) -> Result<(), afc_error_t> {
+        let path = try!(path_to_c_str(path));
+        unsafe { afc_make_directory(self.0, path.as_ptr()) }.to_result()
+    }
+
+    /// Creates a directory and all of its missing parents. Existing directories, including the
+    /// root (`"/"` or `""`), are not an error.
+    pub fn create_dir_all(&self, path: &str) -> Result<(), afc_error_t> {
+        let ancestors = ancestors(path);
+        let last = match ancestors.last() {
+            Some(last) => last,
+            None => return Ok(()),
+        };
+        for dir in &ancestors {
+            match self.create_dir(dir) {
+                Ok(()) | Err(AFC_E_OBJECT_EXISTS) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if try!(self.metadata(last)).is_dir() {
+            Ok(())
+        } else {
+            Err(AFC_E_OBJECT_EXISTS)
+        }
+    }
+
+    /// Removes a file or an empty directory.
+    pub fn remove(&self, path: &str) -> Result<(), afc_error_t> {
+        let path = try!(path_to_c_str(path));
+        unsafe { afc_remove_path(self.0, path.as_ptr()) }.to_result()
+    }
+
+    /// Removes a directory and all of its contents. Symbolic links are removed, not followed.
+    pub fn remove_dir_all(&self, path: &str) -> Result<(), afc_error_t> {
+        let c_path = try!(path_to_c_str(path));
+        match unsafe { afc_remove_path_and_contents(self.0, c_path.as_ptr()) } {
+            AFC_E_SUCCESS => Ok(()),
+            // Devices older than iOS 6 do not understand this request.
+            AFC_E_OP_NOT_SUPPORTED | AFC_E_UNKNOWN_PACKET_TYPE => self.remove_dir_all_manually(path),
+            e => Err(e),
+        }
+    }
+
+    fn remove_dir_all_manually(&self, path: &str) -> Result<(), afc_error_t> {
+        for entry in try!(self.read_dir(path)) {
+            if try!(entry.metadata()).is_dir() {
+                try!(self.remove_dir_all_manually(entry.path()));
+            } else {
+                try!(self.remove(entry.path()));
+            }
+        }
+        self.remove(path)
+    }
+
+    /// Creates a symbolic link at `link` pointing to `target`. The target may be relative to the
+    /// directory of the link, and need not exist.
+    ///
+    /// Fails with `AFC_E_OBJECT_EXISTS` if `link` already exists, and `AFC_E_PERM_DENIED` outside
+    /// the writable area.
+    pub fn symlink(&self, target: &str, link: &str) -> Result<(), afc_error_t> {
+        self.make_link(AFC_SYMLINK, target, link)
+    }
+
+    /// Creates a hard link at `link` to the existing file `target`.
+    ///
+    /// Fails with `AFC_E_OBJECT_EXISTS` if `link` already exists, and `AFC_E_PERM_DENIED` outside
+    /// the writable area.
+    pub fn hard_link(&self, target: &str, link: &str) -> Result<(), afc_error_t> {
+        self.make_link(AFC_HARDLINK, target, link)
+    }
+
+    fn make_link(&self, link_type: afc_link_type_t, target: &str, link: &str) -> Result<(), afc_error_t> {
+        if target.is_empty() || link.is_empty() {
+            return Err(AFC_E_INVALID_ARG);
+        }
+        let target = try!(path_to_c_str(target));
+        let link = try!(path_to_c_str(link));
+        unsafe { afc_make_link(self.0, link_type, target.as_ptr(), link.as_ptr()) }.to_result()
+    }
+
+    /// Copies a local file to `remote` on the device, overwriting any existing file. `progress` is
+    /// called after every chunk and when the file is complete.
+    pub fn upload_file<F: FnMut(&TransferProgress)>(&self, local: &Path, remote: &str, mut progress: F) -> io::Result<TransferProgress> {
+        let mut state = TransferProgress::default();
+        let mut source = try!(fs::File::open(local));
+        let mut target = try!(self.open(remote, AFC_FOPEN_WRONLY).map_err(to_io_error));
+        try!(copy_with_progress(&mut source, &mut target, &mut state, &mut progress));
+        Ok(state)
+    }
+
+    /// Copies a local directory tree to `remote` on the device, creating directories as needed
+    /// and overwriting existing files. Symbolic links and special files are skipped. `progress`
+    /// is called after every chunk and every completed file.
+    pub fn upload_dir<F: FnMut(&TransferProgress)>(&self, local: &Path, remote: &str, mut progress: F) -> io::Result<TransferProgress> {
+        let mut state = TransferProgress::default();
+        try!(self.upload_dir_inner(local, remote, &mut state, &mut progress));
+        Ok(state)
+    }
+
+    fn upload_dir_inner(&self, local: &Path, remote: &str, state: &mut TransferProgress, progress: &mut dyn FnMut(&TransferProgress)) -> io::Result<()> {
+        match self.create_dir(remote) {
+            Ok(()) | Err(AFC_E_OBJECT_EXISTS) => {}
+            Err(e) => return Err(to_io_error(e)),
+        }
+        for entry in try!(fs::read_dir(local)) {
+            let entry = try!(entry);
+            let name = try!(entry.file_name().into_string().map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidData, "file name is not valid UTF-8")
+            }));
+            let remote_path = format!("{}/{}", remote.trim_end_matches('/'), name);
+            // Not following symbolic links, which could otherwise form a cycle.
+            let file_type = try!(entry.file_type());
+            if file_type.is_dir() {
+                try!(self.upload_dir_inner(&entry.path(), &remote_path, state, progress));
+            } else if file_type.is_file() {
+                let mut source = try!(fs::File::open(entry.path()));
+                let mut target = try!(self.open(&remote_path, AFC_FOPEN_WRONLY).map_err(to_io_error));
+                try!(copy_with_progress(&mut source, &mut target, state, progress));
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies the directory tree at `remote` on the device to a local directory, creating
+    /// directories as needed and overwriting existing files. Symbolic links and special files are
+    /// skipped. `progress` is called after every chunk and every completed file.
+    pub fn download_dir<F: FnMut(&TransferProgress)>(&self, remote: &str, local: &Path, mut progress: F) -> io::Result<TransferProgress> {
+        let mut state = TransferProgress::default();
+        try!(self.download_dir_inner(remote, local, &mut state, &mut progress));
+        Ok(state)
+    }
+
+    fn download_dir_inner(&self, remote: &str, local: &Path, state: &mut TransferProgress, progress: &mut dyn FnMut(&TransferProgress)) -> io::Result<()> {
+        try!(fs::create_dir_all(local));
+        for entry in try!(self.read_dir(remote).map_err(to_io_error)) {
+            let metadata = try!(entry.metadata().map_err(to_io_error));
+            let local_path = local.join(entry.name());
+            if metadata.is_dir() {
+                try!(self.download_dir_inner(entry.path(), &local_path, state, progress));
+            } else if metadata.is_file() {
+                let mut source = try!(self.open(entry.path(), AFC_FOPEN_RDONLY).map_err(to_io_error));
+                let mut target = try!(fs::File::create(&local_path));
+                try!(copy_with_progress(&mut source, &mut target, state, progress));
+            }
+        }
+        Ok(())
+    }
+
+    /// Opens a file on the device.
+    ///
+    /// ```rust,no_run
+    /// extern crate libimobiledevice;
+    /// extern crate libimobiledevice_sys;
+    ///
+    /// use libimobiledevice::{Device, LockdownClient, AfcClient};
+    /// use libimobiledevice_sys::afc::AFC_FOPEN_RDONLY;
+    /// use std::io;
+    ///
+    /// # fn main() {
+    /// let device = Device::any().unwrap();
+    /// let lockdown = LockdownClient::new_with_handshake(&device, None).unwrap();
+    /// let service = lockdown.start_service("com.apple.afc").unwrap();
+    /// let afc = AfcClient::new(&device, &service).unwrap();
+    /// let mut file = afc.open("/DCIM/100APPLE/IMG_0001.JPG", AFC_FOPEN_RDONLY).unwrap();
+    /// io::copy(&mut file, &mut io::sink()).unwrap();
+    /// # }
+    /// ```
+    pub fn open(&self, path: &str, mode: afc_file_mode_t) -> Result<AfcFile, afc_error_t> {
+        let path = try!(path_to_c_str(path));
+        let mut handle = 0;
+        try!(unsafe { afc_file_open(self.0, path.as_ptr(), mode, &mut handle) }.to_result());
+        Ok(AfcFile {
+            client: self,
+            handle: handle,
+        })
+    }
+}
+
+/// Lists `path` and each of its parents, outermost first and excluding the root, keeping whether
+/// the path is absolute or relative, e.g. `"/a/b"` gives `["/a", "/a/b"]`.
+fn ancestors(path: &str) -> Vec<String> {
+    let mut current = if path.starts_with('/') { "/".to_owned() } else { String::new() };
+    let mut result = Vec::new();
+    for component in path.split('/').filter(|c| !c.is_empty()) {
+        if !current.is_empty() && !current.ends_with('/') {
+            current.push('/');
+        }
+        current.push_str(component);
+        result.push(current.clone());
+    }
+    result
+}
+
+impl Drop for AfcClient {
+    fn drop(&mut self) {
+        unsafe { afc_client_free(self.0) };
+    }
+}
+
+impl fmt::Debug for AfcClient {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_tuple("AfcClient").field(&self.0).finish()
+    }
+}
+
+/// A NULL-terminated string array returned by AFC. The array is freed when dropped.
+struct StringList(*mut *mut c_char);
+
+impl StringList {
+    fn get(&self, index: usize) -> Option<String> {
+        unsafe {
+            if self.0.is_null() {
+                return None;
+            }
+            let s = *self.0.offset(index as isize);
+            if s.is_null() { None } else { Some(from_c_str_lossy(s)) }
+        }
+    }
+
+    /// Interprets the array as alternating keys and values.
+    fn pairs(&self) -> Vec<(String, String)> {
+        let mut result = Vec::new();
+        let mut index = 0;
+        while let (Some(key), Some(value)) = (self.get(index), self.get(index + 1)) {
+            result.push((key, value));
+            index += 2;
+        }
+        result
+    }
+}
+
+impl Drop for StringList {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { afc_dictionary_free(self.0) };
+        }
+    }
+}
+
+/// Iterator over the entries of a directory, returned by `AfcClient::read_dir`.
+pub struct ReadDir<'a> {
+    client: &'a AfcClient,
+    dir: String,
+    list: StringList,
+    index: usize,
+}
+
+impl<'a> Iterator for ReadDir<'a> {
+    type Item = AfcDirEntry<'a>;
+
+    fn next(&mut self) -> Option<AfcDirEntry<'a>> {
+        loop {
+            let name = match self.list.get(self.index) {
+                Some(name) => name,
+                None => return None,
+            };
+            self.index += 1;
+            if name != "." && name != ".." {
+                return Some(AfcDirEntry {
+                    client: self.client,
+                    path: format!("{}/{}", self.dir, name),
+                    name: name,
+                });
+            }
+        }
+    }
+}
+
+impl<'a> fmt::Debug for ReadDir<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("ReadDir").field("dir", &self.dir).finish()
+    }
+}
+
+/// An entry of a directory. File information is fetched only when requested.
+#[derive(Debug)]
+pub struct AfcDirEntry<'a> {
+    client: &'a AfcClient,
+    path: String,
+    name: String,
+}
+
+impl<'a> AfcDirEntry<'a> {
+    /// The file name of the entry.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The full path of the entry.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Reads the raw file information of the entry.
+    pub fn file_info(&self) -> Result<Vec<(String, String)>, afc_error_t> {
+        self.client.file_info(&self.path)
+    }
+
+    /// Reads the metadata of the entry.
+    pub fn metadata(&self) -> Result<Metadata, afc_error_t> {
+        self.client.metadata(&self.path)
+    }
+}
+
+/// The amount of data transferred so far by `AfcClient::upload_file`, `upload_dir` or
+/// `download_dir`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransferProgress {
+    /// Number of bytes copied.
+    pub bytes: u64,
+    /// Number of files completely copied.
+    pub files: u64,
+}
+
+/// Size of each read/write when transferring files. AFC packets are limited to a few megabytes.
+const CHUNK_SIZE: usize = 65536;
+
+/// Copies a whole file in chunks, reporting progress after each chunk and at the end.
+fn copy_with_progress<R: Read, W: Write>(source: &mut R,
+                                         target: &mut W,
+                                         state: &mut TransferProgress,
+                                         progress: &mut dyn FnMut(&TransferProgress))
+                                         -> io::Result<()> {
+    let mut buf = vec![0; CHUNK_SIZE];
+    loop {
+        let len = match source.read(&mut buf) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        try!(target.write_all(&buf[..len]));
+        state.bytes += len as u64;
+        progress(state);
+    }
+    state.files += 1;
+    progress(state);
+    Ok(())
+}
+
+/// File system information of the device, returned by `AfcClient::device_info`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AfcDeviceInfo {
+    /// The model identifier, e.g. "iPhone9,3".
+    pub model: String,
+    /// The capacity of the media partition in bytes.
+    pub total_bytes: u64,
+    /// The free space of the media partition in bytes.
+    pub free_bytes: u64,
+    /// The block size of the file system in bytes.
+    pub block_size: u64,
+}
+
+impl AfcDeviceInfo {
+    fn from_pairs(info: &[(String, String)]) -> AfcDeviceInfo {
+        let get = |key: &str| find_value(info, key);
+        let number = |key: &str| get(key).and_then(|v| v.parse().ok()).unwrap_or(0);
+        AfcDeviceInfo {
+            model: get("Model").unwrap_or("").to_owned(),
+            total_bytes: number("FSTotalBytes"),
+            free_bytes: number("FSFreeBytes"),
+            block_size: number("FSBlockSize"),
+        }
+    }
+}
+
+/// The type of a file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FileType {
+    File,
+    Directory,
+    Symlink,
+    /// Character and block devices, FIFOs and sockets.
+    Other,
+}
+
+/// Metadata of a file on the device, parsed from `AfcClient::file_info`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Metadata {
+    file_type: FileType,
+    len: u64,
+    blocks: u64,
+    nlink: u64,
+    modified_ns: u64,
+    created_ns: Option<u64>,
+    link_target: Option<String>,
+}
+
+impl Metadata {
+    /// Parses the key/value pairs returned by `afc_get_file_info`. Missing or malformed numbers
+    /// are read as 0.
+    pub fn from_file_info(info: &[(String, String)]) -> Metadata {
+        let get = |key: &str| find_value(info, key);
+        let number = |key: &str| get(key).and_then(|v| v.parse().ok());
+        Metadata {
+            file_type: match get("st_ifmt") {
+                Some("S_IFREG") => FileType::File,
+                Some("S_IFDIR") => FileType::Directory,
+                Some("S_IFLNK") => FileType::Symlink,
+                _ => FileType::Other,
+            },
+            len: number("st_size").unwrap_or(0),
+            blocks: number("st_blocks").unwrap_or(0),
+            nlink: number("st_nlink").unwrap_or(0),
+            modified_ns: number("st_mtime").unwrap_or(0),
+            created_ns: number("st_birthtime"),
+            link_target: get("LinkTarget").map(|s| s.to_owned()),
+        }
+    }
+
+    /// The type of the file.
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    /// The size of the file in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// The number of 512-byte blocks allocated to the file.
+    pub fn blocks(&self) -> u64 {
+        self.blocks
+    }
+
+    /// The number of hard links to the file.
+    pub fn nlink(&self) -> u64 {
+        self.nlink
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.file_type == FileType::File
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.file_type == FileType::Directory
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.file_type == FileType::Symlink
+    }
+
+    /// The last modification time.
+    pub fn modified(&self) -> SystemTime {
+        from_nanos(self.modified_ns)
+    }
+
+    /// The creation time, if reported by the device.
+    pub fn created(&self) -> Option<SystemTime> {
+        self.created_ns.map(from_nanos)
+    }
+
+    /// The target of a symbolic link.
+    pub fn link_target(&self) -> Option<&str> {
+        self.link_target.as_ref().map(|s| &**s)
+    }
+}
+
+/// Finds the value of a key in the pairs returned by `StringList::pairs`.
+fn find_value<'a>(info: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    info.iter().find(|&&(ref k, _)| k == key).map(|&(_, ref v)| &**v)
+}
+
+/// Converts nanoseconds since the Unix epoch, as used by AFC timestamps, into a `SystemTime`.
+fn from_nanos(ns: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::new(ns / 1_000_000_000, (ns % 1_000_000_000) as u32)
+}
+
+/// An open file on the device. The file is closed when dropped.
+#[derive(Debug)]
+pub struct AfcFile<'a> {
+    client: &'a AfcClient,
+    handle: u64,
+}
+
+impl<'a> AfcFile<'a> {
+    /// The raw AFC file handle.
+    pub fn handle(&self) -> u64 {
+        self.handle
+    }
+
+    /// Truncates or extends the file to the given size.
+    pub fn set_len(&self, size: u64) -> io::Result<()> {
+        unsafe { afc_file_truncate(self.client.0, self.handle, size) }.to_result().map_err(to_io_error)
+    }
+
+    /// Acquires an exclusive lock on the file, blocking until it is available. The lock is
+    /// released when the returned guard is dropped.
+    pub fn lock_exclusive<'b>(&'b mut self) -> Result<AfcLockGuard<'a, 'b>, afc_error_t> {
+        self.lock(AFC_LOCK_EX)
+    }
+
+    /// Acquires a shared lock on the file, blocking until it is available. The lock is released
+    /// when the returned guard is dropped.
+    pub fn lock_shared<'b>(&'b mut self) -> Result<AfcLockGuard<'a, 'b>, afc_error_t> {
+        self.lock(AFC_LOCK_SH)
+    }
+
+    fn lock<'b>(&'b mut self, operation: afc_lock_op_t) -> Result<AfcLockGuard<'a, 'b>, afc_error_t> {
+        try!(unsafe { afc_file_lock(self.client.0, self.handle, operation) }.to_result());
+        Ok(AfcLockGuard { file: self })
+    }
+}
+
+/// A held lock on an AFC file. The file can still be read and written through the guard. The lock
+/// is released when dropped.
+#[derive(Debug)]
+pub struct AfcLockGuard<'a: 'b, 'b> {
+    file: &'b mut AfcFile<'a>,
+}
+
+impl<'a, 'b> Deref for AfcLockGuard<'a, 'b> {
+    type Target = AfcFile<'a>;
+    fn deref(&self) -> &AfcFile<'a> {
+        self.file
+    }
+}
+
+impl<'a, 'b> DerefMut for AfcLockGuard<'a, 'b> {
+    fn deref_mut(&mut self) -> &mut AfcFile<'a> {
+        self.file
+    }
+}
+
+impl<'a, 'b> Drop for AfcLockGuard<'a, 'b> {
+    fn drop(&mut self) {
+        unsafe { afc_file_lock(self.file.client.0, self.file.handle, AFC_LOCK_UN) };
+    }
+}
+
+impl<'a> Read for AfcFile<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut bytes_read = 0;
+        let data = buf.as_mut_ptr() as *mut c_char;
+        let result = unsafe { afc_file_read(self.client.0, self.handle, data, clamp_len(buf.len()), &mut bytes_read) };
+        match result {
+            AFC_E_END_OF_DATA => Ok(0),
+            _ => {
+                try!(result.to_result().map_err(to_io_error));
+                Ok(bytes_read as usize)
+            }
+        }
+    }
+}
+
+impl<'a> Write for AfcFile<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut bytes_written = 0;
+        let data = buf.as_ptr() as *const c_char;
+        let result = unsafe { afc_file_write(self.client.0, self.handle, data, clamp_len(buf.len()), &mut bytes_written) };
+        try!(result.to_result().map_err(to_io_error));
+        Ok(bytes_written as usize)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> Seek for AfcFile<'a> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (offset, whence) = match pos {
+            SeekFrom::Start(offset) => {
+                if offset > i64::max_value() as u64 {
+                    return Err(io::Error::new(io::ErrorKind::InvalidInput, "seek offset too large"));
+                }
+                (offset as i64, SEEK_SET)
+            }
+            SeekFrom::Current(offset) => (offset, SEEK_CUR),
+            SeekFrom::End(offset) => (offset, SEEK_END),
+        };
+        let mut position = 0;
+        unsafe {
+            try!(afc_file_seek(self.client.0, self.handle, offset, whence).to_result().map_err(to_io_error));
+            try!(afc_file_tell(self.client.0, self.handle, &mut position).to_result().map_err(to_io_error));
+        }
+        Ok(position)
+    }
+}
+
+impl<'a> Drop for AfcFile<'a> {
+    fn drop(&mut self) {
+        unsafe { afc_file_close(self.client.0, self.handle) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_io_error, ancestors, StringList, Metadata, FileType, AfcDeviceInfo, TransferProgress, copy_with_progress, CHUNK_SIZE};
+    use libimobiledevice_sys::afc::*;
+    use std::io::ErrorKind;
+    use std::ptr::null_mut;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn test_to_io_error() {
+        assert_eq!(to_io_error(AFC_E_OBJECT_NOT_FOUND).kind(), ErrorKind::NotFound);
+        assert_eq!(to_io_error(AFC_E_PERM_DENIED).kind(), ErrorKind::PermissionDenied);
+        assert_eq!(to_io_error(AFC_E_OBJECT_EXISTS).kind(), ErrorKind::AlreadyExists);
+        assert_eq!(to_io_error(AFC_E_IO_ERROR).kind(), ErrorKind::Other);
+    }
+
+    #[test]
+    fn test_string_list_pairs() {
+        let mut strings = [
+            const_cstr!("st_size").as_ptr() as *mut _,
+            const_cstr!("1024").as_ptr() as *mut _,
+            const_cstr!("st_ifmt").as_ptr() as *mut _,
+            const_cstr!("S_IFREG").as_ptr() as *mut _,
+            null_mut(),
+        ];
+        let list = StringList(strings.as_mut_ptr());
+        assert_eq!(list.pairs(), vec![
+            ("st_size".to_owned(), "1024".to_owned()),
+            ("st_ifmt".to_owned(), "S_IFREG".to_owned()),
+        ]);
+        // The array is not malloc'ed, so it must not be freed.
+        ::std::mem::forget(list);
+    }
+
+    #[test]
+    fn test_metadata() {
+        let info = [
+            ("st_size", "1024"),
+            ("st_blocks", "8"),
+            ("st_nlink", "1"),
+            ("st_ifmt", "S_IFLNK"),
+            ("st_mtime", "1500000000123456789"),
+            ("LinkTarget", "../Media"),
+        ].iter().map(|&(k, v)| (k.to_owned(), v.to_owned())).collect::<Vec<_>>();
+        let metadata = Metadata::from_file_info(&info);
+        assert_eq!(metadata.file_type(), FileType::Symlink);
+        assert!(metadata.is_symlink());
+        assert_eq!(metadata.len(), 1024);
+        assert_eq!(metadata.modified(), UNIX_EPOCH + Duration::new(1_500_000_000, 123_456_789));
+        assert_eq!(metadata.created(), None);
+        assert_eq!(metadata.link_target(), Some("../Media"));
+    }
+
+    #[test]
+    fn test_device_info() {
+        let info = [
+            ("Model", "iPhone9,3"),
+            ("FSTotalBytes", "31989469184"),
+            ("FSFreeBytes", "12345678"),
+            ("FSBlockSize", "4096"),
+        ].iter().map(|&(k, v)| (k.to_owned(), v.to_owned())).collect::<Vec<_>>();
+        assert_eq!(AfcDeviceInfo::from_pairs(&info), AfcDeviceInfo {
+            model: "iPhone9,3".to_owned(),
+            total_bytes: 31989469184,
+            free_bytes: 12345678,
+            block_size: 4096,
+        });
+    }
+
+    #[test]
+    fn test_copy_with_progress() {
+        let data = vec![7u8; CHUNK_SIZE + 10];
+        let mut target = Vec::new();
+        let mut state = TransferProgress { bytes: 5, files: 1 };
+        let mut reports = Vec::new();
+        copy_with_progress(&mut &data[..], &mut target, &mut state, &mut |p: &TransferProgress| reports.push(*p)).unwrap();
+        assert_eq!(target, data);
+        assert_eq!(state, TransferProgress { bytes: CHUNK_SIZE as u64 + 15, files: 2 });
+        assert_eq!(reports.last(), Some(&state));
+        assert!(reports.len() >= 3);
+    }
+
+    #[test]
+    fn test_ancestors() {
+        assert_eq!(ancestors("/a/b"), vec!["/a", "/a/b"]);
+        assert_eq!(ancestors("PublicStaging/x/"), vec!["PublicStaging", "PublicStaging/x"]);
+        assert_eq!(ancestors("//a//b"), vec!["/a", "/a/b"]);
+        assert!(ancestors("/").is_empty());
+        assert!(ancestors("").is_empty());
+    }
+}
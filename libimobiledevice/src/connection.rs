@@ -0,0 +1,230 @@
+//! Raw connections to services on a device.
+
+use libimobiledevice_sys::*;
+use libc::{c_char, c_int};
+
+use std::io::{self, Read, Write};
+use std::ops::{Deref, DerefMut};
+use std::ptr::null_mut;
+use std::time::Duration;
+#[cfg(unix)] use std::os::unix::io::{AsRawFd, RawFd};
+
+use device::Device;
+use internal::{ToResult, clamp_len, to_millis};
+
+/// An open connection to a port on the device. The connection is closed when dropped.
+pub struct Connection {
+    raw: idevice_connection_t,
+    /// The socket descriptor, looked up once so `as_raw_fd` cannot fail.
+    fd: c_int,
+    read_timeout: Option<Duration>,
+}
+
+unsafe impl Send for Connection {}
+
+/// Converts a libimobiledevice error code into an I/O error.
+pub fn to_io_error(error: idevice_error_t) -> io::Error {
+    let kind = match error {
+        IDEVICE_E_INVALID_ARG => io::ErrorKind::InvalidInput,
+        IDEVICE_E_NO_DEVICE => io::ErrorKind::NotConnected,
+        IDEVICE_E_NOT_ENOUGH_DATA => io::ErrorKind::UnexpectedEof,
+        IDEVICE_E_BAD_HEADER => io::ErrorKind::InvalidData,
+        IDEVICE_E_TIMEOUT => io::ErrorKind::TimedOut,
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, format!("libimobiledevice error: {:?}", error))
+}
+
+impl Connection {
+    /// Takes ownership of an open connection.
+    ///
+    /// The socket descriptor is looked up here, which always succeeds for an open connection. If
+    /// it does not, `fd()` reports the error and `as_raw_fd()` returns -1.
+    pub unsafe fn from_ptr(connection: idevice_connection_t) -> Connection {
+        let mut fd = -1;
+        if idevice_connection_get_fd(connection, &mut fd).to_result().is_err() {
+            fd = -1;
+        }
+        Connection {
+            raw: connection,
+            fd: fd,
+            read_timeout: None,
+        }
+    }
+
+    pub fn as_ptr(&self) -> idevice_connection_t {
+        self.raw
+    }
+
+    /// Connects to the given port on the device.
+    pub fn connect(device: &Device, port: u16) -> Result<Connection, idevice_error_t> {
+        let mut connection = null_mut();
+        unsafe {
+            try!(idevice_connect(device.as_ptr(), port, &mut connection).to_result());
+            Ok(Connection::from_ptr(connection))
+        }
+    }
+
+    /// Enables SSL on the connection. SSL is disabled again when the returned session is dropped.
+    pub fn start_ssl(&mut self) -> Result<SslSession, idevice_error_t> {
+        try!(unsafe { idevice_connection_enable_ssl(self.raw) }.to_result());
+        Ok(SslSession { connection: self })
+    }
+
+    /// Sets the read timeout. Reads which do not receive any data within the timeout fail with
+    /// `ErrorKind::TimedOut`. `None` means reads block indefinitely.
+    ///
+    /// Like `TcpStream::set_read_timeout`, a zero duration is rejected with
+    /// `ErrorKind::InvalidInput`.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        if timeout == Some(Duration::new(0, 0)) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "cannot set a 0 duration timeout"));
+        }
+        self.read_timeout = timeout;
+        Ok(())
+    }
+
+    /// Returns the read timeout of this connection.
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.read_timeout
+    }
+
+    /// Obtains the file descriptor of the underlying socket.
+    ///
+    /// The descriptor is meant for readiness notification (`poll`, epoll, mio, ...). Reading or
+    /// writing it directly bypasses SSL and the library's buffering.
+    pub fn fd(&self) -> Result<c_int, idevice_error_t> {
+        if self.fd >= 0 {
+            return Ok(self.fd);
+        }
+        let mut fd = -1;
+        try!(unsafe { idevice_connection_get_fd(self.raw, &mut fd) }.to_result());
+        Ok(fd)
+    }
+}
+
+#[cfg(unix)]
+impl AsRawFd for Connection {
+    /// Returns the socket descriptor, see `Connection::fd`.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        unsafe { idevice_disconnect(self.raw) };
+    }
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut received = 0;
+        let data = buf.as_mut_ptr() as *mut c_char;
+        let len = clamp_len(buf.len());
+        let result = unsafe {
+            match self.read_timeout {
+                None => idevice_connection_receive(self.raw, data, len, &mut received),
+                Some(timeout) => idevice_connection_receive_timeout(self.raw, data, len, &mut received, to_millis(timeout)),
+            }
+        };
+        match result {
+            #[cfg(libimobiledevice_1_3)]
+            IDEVICE_E_TIMEOUT => Err(io::Error::new(io::ErrorKind::TimedOut, "receive timed out")),
+            // libimobiledevice 1.2 reports an expired timeout as an unknown error.
+            #[cfg(not(libimobiledevice_1_3))]
+            IDEVICE_E_UNKNOWN_ERROR if self.read_timeout.is_some() && received == 0 => {
+                Err(io::Error::new(io::ErrorKind::TimedOut, "receive timed out"))
+            }
+            _ => {
+                try!(result.to_result().map_err(to_io_error));
+                Ok(received as usize)
+            }
+        }
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut sent = 0;
+        let data = buf.as_ptr() as *const c_char;
+        let result = unsafe { idevice_connection_send(self.raw, data, clamp_len(buf.len()), &mut sent) };
+        try!(result.to_result().map_err(to_io_error));
+        Ok(sent as usize)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// An SSL-enabled period of a connection. Reads and writes through the session are encrypted.
+/// SSL is disabled when the session is dropped.
+pub struct SslSession<'a> {
+    connection: &'a mut Connection,
+}
+
+impl<'a> Deref for SslSession<'a> {
+    type Target = Connection;
+    fn deref(&self) -> &Connection {
+        self.connection
+    }
+}
+
+impl<'a> DerefMut for SslSession<'a> {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.connection
+    }
+}
+
+impl<'a> Read for SslSession<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.connection.read(buf)
+    }
+}
+
+impl<'a> Write for SslSession<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.connection.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.connection.flush()
+    }
+}
+
+impl<'a> Drop for SslSession<'a> {
+    fn drop(&mut self) {
+        unsafe { idevice_connection_disable_ssl(self.connection.raw) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_io_error, clamp_len, to_millis};
+    use libimobiledevice_sys::*;
+    use std::io::ErrorKind;
+    use std::time::Duration;
+    use libc::c_uint;
+
+    #[test]
+    fn test_to_io_error() {
+        assert_eq!(to_io_error(IDEVICE_E_NO_DEVICE).kind(), ErrorKind::NotConnected);
+        assert_eq!(to_io_error(IDEVICE_E_INVALID_ARG).kind(), ErrorKind::InvalidInput);
+        assert_eq!(to_io_error(IDEVICE_E_SSL_ERROR).kind(), ErrorKind::Other);
+        assert_eq!(to_io_error(IDEVICE_E_TIMEOUT).kind(), ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn test_clamp_len() {
+        assert_eq!(clamp_len(12), 12);
+        assert_eq!(clamp_len(::std::u32::MAX as usize), ::std::u32::MAX);
+    }
+
+    #[test]
+    fn test_to_millis() {
+        assert_eq!(to_millis(Duration::from_millis(1500)), 1500);
+        assert_eq!(to_millis(Duration::new(0, 1)), 1);
+        assert_eq!(to_millis(Duration::new(u64::max_value(), 0)), c_uint::MAX);
+    }
+}
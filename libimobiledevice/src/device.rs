@@ -0,0 +1,242 @@
+//! Devices connected through usbmuxd.
+
+use libimobiledevice_sys::*;
+use libplist::c_str::{ToCStr, from_c_str_lossy};
+use libc::c_char;
+
+use std::ptr::{null, null_mut};
+use std::fmt;
+
+use connection::Connection;
+use internal::{ToResult, take_string};
+#[cfg(feature="libimobiledevice-1-3")] use internal::opt_ptr;
+#[cfg(feature="libimobiledevice-1-3")] use std::ops::{BitOr, BitOrAssign};
+
+/// An owned handle to an iOS device. The handle is freed when dropped.
+pub struct Device(idevice_t);
+
+unsafe impl Send for Device {}
+
+impl Device {
+    pub unsafe fn from_ptr(device: idevice_t) -> Device {
+        Device(device)
+    }
+
+    pub fn as_ptr(&self) -> idevice_t {
+        self.0
+    }
+
+    /// Opens the device with the given UDID.
+    pub fn new(udid: &str) -> Result<Device, idevice_error_t> {
+        let udid = try!(udid.to_c_str().map_err(|_| IDEVICE_E_INVALID_ARG));
+        Device::open(udid.as_ptr())
+    }
+
+    /// Opens the first device found.
+    pub fn any() -> Result<Device, idevice_error_t> {
+        Device::open(null())
+    }
+
+    fn open(udid: *const c_char) -> Result<Device, idevice_error_t> {
+        let mut device = null_mut();
+        unsafe {
+            try!(idevice_new(&mut device, udid).to_result());
+            Ok(Device::from_ptr(device))
+        }
+    }
+
+    /// Opens a device, choosing how it is looked up. If `udid` is `None`, opens the first device
+    /// found.
+    ///
+    /// ```rust,no_run
+    /// use libimobiledevice::{Device, LookupOptions};
+    ///
+    /// let device = Device::with_options(None, LookupOptions::NETWORK).unwrap();
+    /// ```
+    #[cfg(feature="libimobiledevice-1-3")]
+    pub fn with_options(udid: Option<&str>, options: LookupOptions) -> Result<Device, idevice_error_t> {
+        let udid = match udid {
+            Some(udid) => Some(try!(udid.to_c_str().map_err(|_| IDEVICE_E_INVALID_ARG))),
+            None => None,
+        };
+        let mut device = null_mut();
+        unsafe {
+            try!(idevice_new_with_options(&mut device, opt_ptr(&udid), options.bits()).to_result());
+            Ok(Device::from_ptr(device))
+        }
+    }
+
+    /// Obtains the UDID of the device.
+    pub fn udid(&self) -> Result<String, idevice_error_t> {
+        let mut udid = null_mut();
+        unsafe {
+            try!(idevice_get_udid(self.0, &mut udid).to_result());
+            Ok(take_string(udid))
+        }
+    }
+
+    /// Connects to the given port on the device, e.g. a service port returned by lockdownd.
+    pub fn connect(&self, port: u16) -> Result<Connection, idevice_error_t> {
+        Connection::connect(self, port)
+    }
+
+    /// Obtains the usbmuxd handle of the device.
+    pub fn handle(&self) -> Result<u32, idevice_error_t> {
+        let mut handle = 0;
+        unsafe {
+            try!(idevice_get_handle(self.0, &mut handle).to_result());
+        }
+        Ok(handle)
+    }
+}
+
+/// Lists the UDIDs of all connected devices.
+pub fn devices() -> Result<Vec<String>, idevice_error_t> {
+    let mut list = null_mut();
+    let mut count = 0;
+    unsafe {
+        try!(idevice_get_device_list(&mut list, &mut count).to_result());
+        let result = (0..count as isize).map(|i| from_c_str_lossy(*list.offset(i))).collect();
+        idevice_device_list_free(list);
+        Ok(result)
+    }
+}
+
+/// Where `Device::with_options` looks for the device.
+#[cfg(feature="libimobiledevice-1-3")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LookupOptions(idevice_options);
+
+#[cfg(feature="libimobiledevice-1-3")]
+impl LookupOptions {
+    /// Look for devices connected by USB.
+    pub const USBMUX: LookupOptions = LookupOptions(IDEVICE_LOOKUP_USBMUX);
+    /// Look for devices connected over the network.
+    pub const NETWORK: LookupOptions = LookupOptions(IDEVICE_LOOKUP_NETWORK);
+    /// If a device is connected both ways, use the network connection.
+    pub const PREFER_NETWORK: LookupOptions = LookupOptions(IDEVICE_LOOKUP_PREFER_NETWORK);
+
+    /// No flags, which the C library treats like `USBMUX`.
+    pub fn empty() -> LookupOptions {
+        LookupOptions(0)
+    }
+
+    /// The raw flags passed to the C API.
+    pub fn bits(self) -> idevice_options {
+        self.0
+    }
+
+    /// Whether all flags in `other` are set.
+    pub fn contains(self, other: LookupOptions) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+#[cfg(feature="libimobiledevice-1-3")]
+impl BitOr for LookupOptions {
+    type Output = LookupOptions;
+    fn bitor(self, other: LookupOptions) -> LookupOptions {
+        LookupOptions(self.0 | other.0)
+    }
+}
+
+#[cfg(feature="libimobiledevice-1-3")]
+impl BitOrAssign for LookupOptions {
+    fn bitor_assign(&mut self, other: LookupOptions) {
+        self.0 |= other.0;
+    }
+}
+
+/// How a device is connected to the host.
+#[cfg(feature="libimobiledevice-1-3")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConnectionType {
+    /// Connected by a USB cable.
+    Usb,
+    /// Connected over Wi-Fi.
+    Network,
+    /// A connection type unknown to this crate.
+    Other(u32),
+}
+
+#[cfg(feature="libimobiledevice-1-3")]
+impl From<idevice_connection_type> for ConnectionType {
+    fn from(conn_type: idevice_connection_type) -> ConnectionType {
+        match conn_type {
+            CONNECTION_USBMUXD => ConnectionType::Usb,
+            CONNECTION_NETWORK => ConnectionType::Network,
+            other => ConnectionType::Other(other as u32),
+        }
+    }
+}
+
+/// A device found by `devices_extended`.
+#[cfg(feature="libimobiledevice-1-3")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceListEntry {
+    pub udid: String,
+    pub connection_type: ConnectionType,
+}
+
+/// Lists all connected devices, including those reachable over the network. A device connected
+/// both ways appears once for each connection.
+#[cfg(feature="libimobiledevice-1-3")]
+pub fn devices_extended() -> Result<Vec<DeviceListEntry>, idevice_error_t> {
+    let mut list = null_mut();
+    let mut count = 0;
+    unsafe {
+        try!(idevice_get_device_list_extended(&mut list, &mut count).to_result());
+        let result = (0..count as isize).map(|i| {
+            let info = &**list.offset(i);
+            DeviceListEntry {
+                udid: from_c_str_lossy(info.udid),
+                connection_type: ConnectionType::from(info.conn_type),
+            }
+        }).collect();
+        idevice_device_list_extended_free(list);
+        Ok(result)
+    }
+}
+
+impl Drop for Device {
+    fn drop(&mut self) {
+        unsafe { idevice_free(self.0) };
+    }
+}
+
+impl fmt::Debug for Device {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_tuple("Device").field(&self.udid().ok()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Device;
+    use libimobiledevice_sys::IDEVICE_E_INVALID_ARG;
+
+    #[test]
+    fn test_new_with_interior_nul() {
+        assert_eq!(Device::new("abc\0def").err(), Some(IDEVICE_E_INVALID_ARG));
+    }
+
+    #[cfg(feature="libimobiledevice-1-3")]
+    #[test]
+    fn test_connection_type() {
+        use super::ConnectionType;
+        use libimobiledevice_sys::{CONNECTION_USBMUXD, CONNECTION_NETWORK};
+        assert_eq!(ConnectionType::from(CONNECTION_USBMUXD), ConnectionType::Usb);
+        assert_eq!(ConnectionType::from(CONNECTION_NETWORK), ConnectionType::Network);
+        assert_eq!(ConnectionType::from(7), ConnectionType::Other(7));
+    }
+
+    #[cfg(feature="libimobiledevice-1-3")]
+    #[test]
+    fn test_lookup_options() {
+        use super::LookupOptions;
+        let options = LookupOptions::USBMUX | LookupOptions::NETWORK;
+        assert_eq!(options.bits(), 6);
+        assert!(options.contains(LookupOptions::NETWORK));
+        assert!(!options.contains(LookupOptions::PREFER_NETWORK));
+    }
+}
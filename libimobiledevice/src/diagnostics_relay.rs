@@ -0,0 +1,187 @@
+//! Client of the diagnostics relay service, which reports hardware diagnostics and controls the
+//! power state of a device.
+
+use libimobiledevice_sys::diagnostics_relay::*;
+use libplist::{OwnedNode, FromPlistNode};
+use libplist::c_str::ToCStr;
+use libc::c_int;
+
+use std::fmt;
+use std::ops::{BitOr, BitOrAssign};
+use std::ptr::null_mut;
+
+use device::Device;
+use error::Error;
+use lockdown::ServiceDescriptor;
+use internal::{ToResult, opt_ptr, to_label, to_opt_c_str};
+
+/// Options for `DiagnosticsRelayClient::restart` and `shutdown`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ActionFlags(c_int);
+
+impl ActionFlags {
+    /// Perform the action only after the client disconnects.
+    pub const WAIT_FOR_DISCONNECT: ActionFlags = ActionFlags(DIAGNOSTICS_RELAY_ACTION_FLAG_WAIT_FOR_DISCONNECT);
+    /// Show a "pass" screen before the action.
+    pub const DISPLAY_PASS: ActionFlags = ActionFlags(DIAGNOSTICS_RELAY_ACTION_FLAG_DISPLAY_PASS);
+    /// Show a "fail" screen before the action.
+    pub const DISPLAY_FAIL: ActionFlags = ActionFlags(DIAGNOSTICS_RELAY_ACTION_FLAG_DISPLAY_FAIL);
+
+    /// No flags.
+    pub fn empty() -> ActionFlags {
+        ActionFlags(0)
+    }
+
+    /// The raw flags passed to the C API.
+    pub fn bits(self) -> c_int {
+        self.0
+    }
+
+    /// Whether all flags in `other` are set.
+    pub fn contains(self, other: ActionFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for ActionFlags {
+    type Output = ActionFlags;
+    fn bitor(self, other: ActionFlags) -> ActionFlags {
+        ActionFlags(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for ActionFlags {
+    fn bitor_assign(&mut self, other: ActionFlags) {
+        self.0 |= other.0;
+    }
+}
+
+/// An owned diagnostics relay client. The client is freed when dropped.
+pub struct DiagnosticsRelayClient(diagnostics_relay_client_t);
+
+unsafe impl Send for DiagnosticsRelayClient {}
+
+impl DiagnosticsRelayClient {
+    pub unsafe fn from_ptr(client: diagnostics_relay_client_t) -> DiagnosticsRelayClient {
+        DiagnosticsRelayClient(client)
+    }
+
+    pub fn as_ptr(&self) -> diagnostics_relay_client_t {
+        self.0
+    }
+
+    /// Connects to a diagnostics relay service started by `LockdownClient::start_service`.
+    pub fn new(device: &Device, service: &ServiceDescriptor) -> Result<DiagnosticsRelayClient, diagnostics_relay_error_t> {
+        let mut client = null_mut();
+        unsafe {
+            try!(diagnostics_relay_client_new(device.as_ptr(), service.as_ptr(), &mut client).to_result());
+            Ok(DiagnosticsRelayClient::from_ptr(client))
+        }
+    }
+
+    /// Starts the diagnostics relay service through lockdownd and connects to it. If `label` is
+    /// `None`, the name of the current executable is used.
+    pub fn start_service(device: &Device, label: Option<&str>) -> Result<DiagnosticsRelayClient, diagnostics_relay_error_t> {
+        let label = try!(to_label(label).map_err(|_| DIAGNOSTICS_RELAY_E_INVALID_ARG));
+        let mut client = null_mut();
+        unsafe {
+            try!(diagnostics_relay_client_start_service(device.as_ptr(), &mut client, label.as_ptr()).to_result());
+            Ok(DiagnosticsRelayClient::from_ptr(client))
+        }
+    }
+
+    /// Tells the service to close the connection.
+    pub fn goodbye(&self) -> Result<(), diagnostics_relay_error_t> {
+        unsafe { diagnostics_relay_goodbye(self.0) }.to_result()
+    }
+
+    /// Puts the device into deep sleep mode and disconnects from the host.
+    pub fn sleep(&self) -> Result<(), diagnostics_relay_error_t> {
+        unsafe { diagnostics_relay_sleep(self.0) }.to_result()
+    }
+
+    /// Restarts the device.
+    pub fn restart(&self, flags: ActionFlags) -> Result<(), diagnostics_relay_error_t> {
+        unsafe { diagnostics_relay_restart(self.0, flags.bits()) }.to_result()
+    }
+
+    /// Shuts down the device.
+    pub fn shutdown(&self, flags: ActionFlags) -> Result<(), diagnostics_relay_error_t> {
+        unsafe { diagnostics_relay_shutdown(self.0, flags.bits()) }.to_result()
+    }
+
+    /// Requests diagnostics of the given kind: `"All"`, `"WiFi"`, `"GasGauge"` or `"NAND"`.
+    pub fn request_diagnostics(&self, kind: &str) -> Result<OwnedNode, diagnostics_relay_error_t> {
+        let kind = try!(kind.to_c_str().map_err(|_| DIAGNOSTICS_RELAY_E_INVALID_ARG));
+        let mut result = null_mut();
+        unsafe {
+            try!(diagnostics_relay_request_diagnostics(self.0, kind.as_ptr(), &mut result).to_result());
+            OwnedNode::try_from_ptr(result).ok_or(DIAGNOSTICS_RELAY_E_PLIST_ERROR)
+        }
+    }
+
+    /// Queries an IORegistry entry by name and/or class, e.g. `(None, Some("IOPMPowerSource"))`
+    /// for battery information. At least one of them must be given.
+    pub fn ioregistry_entry(&self, name: Option<&str>, class: Option<&str>) -> Result<OwnedNode, diagnostics_relay_error_t> {
+        let name = try!(to_opt_c_str(name).map_err(|_| DIAGNOSTICS_RELAY_E_INVALID_ARG));
+        let class = try!(to_opt_c_str(class).map_err(|_| DIAGNOSTICS_RELAY_E_INVALID_ARG));
+        let mut result = null_mut();
+        unsafe {
+            try!(diagnostics_relay_query_ioregistry_entry(self.0, opt_ptr(&name), opt_ptr(&class), &mut result).to_result());
+            OwnedNode::try_from_ptr(result).ok_or(DIAGNOSTICS_RELAY_E_PLIST_ERROR)
+        }
+    }
+
+    /// Queries an IORegistry entry and converts it to a Rust type. A result which cannot be
+    /// converted is reported as `Error::Plist`.
+    pub fn ioregistry_entry_as<T: FromPlistNode>(&self, name: Option<&str>, class: Option<&str>) -> Result<T, Error> {
+        let node = try!(self.ioregistry_entry(name, class));
+        Ok(try!(T::from_plist_node(&node)))
+    }
+
+    /// Dumps an IORegistry plane, e.g. `"IODeviceTree"`. If `plane` is `None`, dumps the default
+    /// plane.
+    pub fn ioregistry_plane(&self, plane: Option<&str>) -> Result<OwnedNode, diagnostics_relay_error_t> {
+        let plane = try!(to_opt_c_str(plane).map_err(|_| DIAGNOSTICS_RELAY_E_INVALID_ARG));
+        let mut result = null_mut();
+        unsafe {
+            try!(diagnostics_relay_query_ioregistry_plane(self.0, opt_ptr(&plane), &mut result).to_result());
+            OwnedNode::try_from_ptr(result).ok_or(DIAGNOSTICS_RELAY_E_PLIST_ERROR)
+        }
+    }
+
+    /// Dumps an IORegistry plane and converts it to a Rust type. A result which cannot be
+    /// converted is reported as `Error::Plist`.
+    pub fn ioregistry_plane_as<T: FromPlistNode>(&self, plane: Option<&str>) -> Result<T, Error> {
+        let node = try!(self.ioregistry_plane(plane));
+        Ok(try!(T::from_plist_node(&node)))
+    }
+}
+
+impl Drop for DiagnosticsRelayClient {
+    fn drop(&mut self) {
+        unsafe { diagnostics_relay_client_free(self.0) };
+    }
+}
+
+impl fmt::Debug for DiagnosticsRelayClient {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_tuple("DiagnosticsRelayClient").field(&self.0).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ActionFlags;
+
+    #[test]
+    fn test_action_flags() {
+        let mut flags = ActionFlags::empty();
+        assert_eq!(flags.bits(), 0);
+        flags |= ActionFlags::WAIT_FOR_DISCONNECT;
+        let flags = flags | ActionFlags::DISPLAY_PASS;
+        assert_eq!(flags.bits(), 6);
+        assert!(flags.contains(ActionFlags::DISPLAY_PASS));
+        assert!(!flags.contains(ActionFlags::DISPLAY_FAIL));
+    }
+}
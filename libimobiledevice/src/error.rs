@@ -0,0 +1,131 @@
+//! Error types.
+
+use libimobiledevice_sys::idevice_error_t;
+use libimobiledevice_sys::lockdown::lockdownd_error_t;
+#[cfg(feature="afc")] use libimobiledevice_sys::afc::afc_error_t;
+#[cfg(feature="diagnostics-relay")] use libimobiledevice_sys::diagnostics_relay::diagnostics_relay_error_t;
+#[cfg(feature="syslog-relay")] use libimobiledevice_sys::syslog_relay::syslog_relay_error_t;
+#[cfg(feature="installation-proxy")] use libimobiledevice_sys::installation_proxy::instproxy_error_t;
+use libplist::PlistError;
+
+use std::error;
+use std::fmt;
+
+/// Any error reported by this crate. Every specific error type converts into this one, so
+/// functions mixing several services can use `?` (or `try!`) throughout.
+///
+/// ```rust,no_run
+/// use libimobiledevice::{Device, LockdownClient, Error};
+///
+/// fn product_version() -> Result<String, Error> {
+///     let device = try!(Device::any());
+///     let client = try!(LockdownClient::new_with_handshake(&device, None));
+///     client.get_value_as(None, Some("ProductVersion"))
+/// }
+/// ```
+#[derive(Debug)]
+pub enum Error {
+    /// Error from the device connection layer.
+    Idevice(idevice_error_t),
+    /// Error from lockdownd.
+    Lockdown(lockdownd_error_t),
+    /// Error from the AFC service.
+    #[cfg(feature="afc")]
+    Afc(afc_error_t),
+    /// Error from the diagnostics relay service.
+    #[cfg(feature="diagnostics-relay")]
+    DiagnosticsRelay(diagnostics_relay_error_t),
+    /// Error from the syslog relay service.
+    #[cfg(feature="syslog-relay")]
+    SyslogRelay(syslog_relay_error_t),
+    /// Error from the installation proxy service.
+    #[cfg(feature="installation-proxy")]
+    InstallationProxy(instproxy_error_t),
+    /// A plist value could not be converted to the requested Rust type.
+    Plist(PlistError),
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::Idevice(_) => "libimobiledevice error",
+            Error::Lockdown(_) => "lockdownd error",
+            #[cfg(feature="afc")]
+            Error::Afc(_) => "AFC error",
+            #[cfg(feature="diagnostics-relay")]
+            Error::DiagnosticsRelay(_) => "diagnostics relay error",
+            #[cfg(feature="syslog-relay")]
+            Error::SyslogRelay(_) => "syslog relay error",
+            #[cfg(feature="installation-proxy")]
+            Error::InstallationProxy(_) => "installation proxy error",
+            Error::Plist(_) => "plist conversion error",
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            Error::Plist(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Idevice(e) => write!(formatter, "libimobiledevice error: {:?}", e),
+            Error::Lockdown(e) => write!(formatter, "lockdownd error: {:?}", e),
+            #[cfg(feature="afc")]
+            Error::Afc(e) => write!(formatter, "AFC error: {:?}", e),
+            #[cfg(feature="diagnostics-relay")]
+            Error::DiagnosticsRelay(e) => write!(formatter, "diagnostics relay error: {:?}", e),
+            #[cfg(feature="syslog-relay")]
+            Error::SyslogRelay(e) => write!(formatter, "syslog relay error: {:?}", e),
+            #[cfg(feature="installation-proxy")]
+            Error::InstallationProxy(e) => write!(formatter, "installation proxy error: {:?}", e),
+            Error::Plist(ref e) => e.fmt(formatter),
+        }
+    }
+}
+
+macro_rules! impl_from_for_error {
+    ($($(#[$attr:meta])* $ty:ty => $variant:ident),*) => {
+        $($(#[$attr])* impl From<$ty> for Error {
+            fn from(e: $ty) -> Error {
+                Error::$variant(e)
+            }
+        })*
+    }
+}
+
+impl_from_for_error! {
+    idevice_error_t => Idevice,
+    lockdownd_error_t => Lockdown,
+    #[cfg(feature="afc")] afc_error_t => Afc,
+    #[cfg(feature="diagnostics-relay")] diagnostics_relay_error_t => DiagnosticsRelay,
+    #[cfg(feature="syslog-relay")] syslog_relay_error_t => SyslogRelay,
+    #[cfg(feature="installation-proxy")] instproxy_error_t => InstallationProxy,
+    PlistError => Plist
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+    use libimobiledevice_sys::IDEVICE_E_NO_DEVICE;
+    use libimobiledevice_sys::lockdown::{lockdownd_error_t, LOCKDOWN_E_PASSWORD_PROTECTED};
+
+    fn fails_with_lockdown() -> Result<(), Error> {
+        try!(Err(LOCKDOWN_E_PASSWORD_PROTECTED));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from() {
+        match fails_with_lockdown() {
+            Err(Error::Lockdown(LOCKDOWN_E_PASSWORD_PROTECTED)) => {}
+            e => panic!("unexpected {:?}", e),
+        }
+        assert_eq!(Error::from(IDEVICE_E_NO_DEVICE).to_string(), "libimobiledevice error: NoDevice");
+        assert_eq!(Error::from(lockdownd_error_t::from(-99)).to_string(), "lockdownd error: Unknown(-99)");
+    }
+}
@@ -0,0 +1,337 @@
+//! Device attach/detach notifications.
+//!
+//! libimobiledevice supports only one event callback per process. This module installs that
+//! callback while any closure is subscribed and dispatches every event to all closures registered
+//! through [`subscribe`](fn.subscribe.html), so independent parts of an application can listen to
+//! events at the same time.
+
+use libimobiledevice_sys::*;
+use libplist::c_str::from_c_str_lossy;
+use libc::c_void;
+
+use std::cell::Cell;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr::null_mut;
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use device::{Device, devices};
+use internal::ToResult;
+
+/// An attach or detach event of a device.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeviceEvent {
+    /// A device with the given UDID has been connected.
+    Added(String),
+    /// A device with the given UDID has been disconnected.
+    Removed(String),
+}
+
+impl DeviceEvent {
+    /// The UDID of the device this event is about.
+    pub fn udid(&self) -> &str {
+        match *self {
+            DeviceEvent::Added(ref udid) | DeviceEvent::Removed(ref udid) => udid,
+        }
+    }
+}
+
+type Callback = Box<dyn FnMut(DeviceEvent) + Send>;
+
+/// The registered closures and the state of the C subscription they share.
+struct Registry {
+    callbacks: Vec<(usize, Arc<Mutex<Callback>>)>,
+    next_id: usize,
+    /// Whether the C callback is installed.
+    installed: bool,
+    /// Whether a thread is calling `idevice_event_subscribe` or `idevice_event_unsubscribe`.
+    busy: bool,
+}
+
+/// Only ever locked briefly, and never while calling into libimobiledevice, since unsubscribing
+/// joins the event thread, which locks the registry as well.
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    callbacks: Vec::new(),
+    next_id: 0,
+    installed: false,
+    busy: false,
+});
+
+/// Notified whenever `Registry::busy` is cleared.
+static IDLE: Condvar = Condvar::new();
+
+thread_local! {
+    /// Whether this thread is running event callbacks. Such a thread must never wait for the
+    /// subscription to change, as unsubscribing waits for it in turn.
+    static IN_CALLBACK: Cell<bool> = Cell::new(false);
+}
+
+fn register(callback: Callback) -> usize {
+    let mut registry = REGISTRY.lock().unwrap();
+    let id = registry.next_id;
+    registry.next_id += 1;
+    registry.callbacks.push((id, Arc::new(Mutex::new(callback))));
+    id
+}
+
+fn unregister(id: usize) {
+    REGISTRY.lock().unwrap().callbacks.retain(|entry| entry.0 != id);
+}
+
+/// Installs or removes the C callback until it matches whether any closure is registered. Threads
+/// calling this concurrently take turns.
+fn sync_subscription() -> Result<(), idevice_error_t> {
+    let mut registry = REGISTRY.lock().unwrap();
+    loop {
+        while registry.busy {
+            registry = IDLE.wait(registry).unwrap();
+        }
+        let wanted = !registry.callbacks.is_empty();
+        if wanted == registry.installed {
+            return Ok(());
+        }
+        registry.busy = true;
+        drop(registry);
+
+        let result = unsafe {
+            if wanted {
+                idevice_event_subscribe(trampoline, null_mut())
+            } else {
+                idevice_event_unsubscribe()
+            }
+        };
+
+        registry = REGISTRY.lock().unwrap();
+        registry.busy = false;
+        IDLE.notify_all();
+        try!(result.to_result());
+        registry.installed = wanted;
+    }
+}
+
+fn dispatch(event: DeviceEvent) {
+    let callbacks = REGISTRY.lock().unwrap().callbacks.iter().map(|entry| entry.1.clone()).collect::<Vec<_>>();
+    let was_in_callback = IN_CALLBACK.with(|c| c.replace(true));
+    for callback in callbacks {
+        // A callback which panicked before is still called, hence ignoring the poison.
+        let mut callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+        // Unwinding into the C event thread is not possible, so a panicking callback is skipped.
+        let _ = catch_unwind(AssertUnwindSafe(|| (*callback)(event.clone())));
+    }
+    IN_CALLBACK.with(|c| c.set(was_in_callback));
+}
+
+unsafe extern "C" fn trampoline(event: *const idevice_event_t, _: *mut c_void) {
+    let event = &*event;
+    let udid = from_c_str_lossy(event.udid);
+    dispatch(match event.event {
+        IDEVICE_DEVICE_ADD => DeviceEvent::Added(udid),
+        IDEVICE_DEVICE_REMOVE => DeviceEvent::Removed(udid),
+        // e.g. IDEVICE_DEVICE_PAIRED from libimobiledevice 1.3.
+        _ => return,
+    });
+}
+
+/// Keeps a closure subscribed to device events. The closure is unsubscribed when dropped, though
+/// it may still receive an event whose dispatch had already begun.
+///
+/// Guards may be created and dropped from inside event callbacks. If the last guard is dropped
+/// there, the C callback stays installed until the next call to `subscribe` or guard drop on
+/// another thread, as the event thread cannot wait for itself to finish.
+#[must_use]
+#[derive(Debug)]
+pub struct SubscriptionGuard {
+    id: usize,
+}
+
+/// Calls `f` on a background thread whenever a device is connected or disconnected.
+pub fn subscribe<F: FnMut(DeviceEvent) + Send + 'static>(f: F) -> Result<SubscriptionGuard, idevice_error_t> {
+    let id = register(Box::new(f));
+    // Inside a callback, the C callback is installed already, or about to be reinstalled by the
+    // thread currently unsubscribing.
+    if !IN_CALLBACK.with(|c| c.get()) {
+        if let Err(e) = sync_subscription() {
+            unregister(id);
+            return Err(e);
+        }
+    }
+    Ok(SubscriptionGuard { id: id })
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        unregister(self.id);
+        if !IN_CALLBACK.with(|c| c.get()) {
+            let _ = sync_subscription();
+        }
+    }
+}
+
+/// A blocking iterator over device events.
+///
+/// ```rust,no_run
+/// use libimobiledevice::{DeviceMonitor, DeviceEvent};
+///
+/// for event in DeviceMonitor::new().unwrap() {
+///     match event {
+///         DeviceEvent::Added(udid) => println!("connected: {}", udid),
+///         DeviceEvent::Removed(udid) => println!("disconnected: {}", udid),
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DeviceMonitor {
+    receiver: Receiver<DeviceEvent>,
+    _guard: SubscriptionGuard,
+}
+
+impl DeviceMonitor {
+    /// Starts listening to device events.
+    ///
+    /// usbmuxd reports the devices already connected as `Added` events when the process first
+    /// subscribes, but not to subscribers added later. Use `devices()` to list them reliably.
+    pub fn new() -> Result<DeviceMonitor, idevice_error_t> {
+        let (sender, receiver) = channel();
+        let guard = try!(subscribe(move |event| {
+            let _ = sender.send(event);
+        }));
+        Ok(DeviceMonitor {
+            receiver: receiver,
+            _guard: guard,
+        })
+    }
+
+    /// Waits for the next event for at most `timeout`. Returns `None` if the timeout elapsed.
+    pub fn next_timeout(&mut self, timeout: Duration) -> Option<DeviceEvent> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(event) => Some(event),
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => None,
+        }
+    }
+
+    /// Returns the next event if one is already pending.
+    pub fn try_next(&mut self) -> Option<DeviceEvent> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Iterator for DeviceMonitor {
+    type Item = DeviceEvent;
+
+    /// Blocks until the next event.
+    fn next(&mut self) -> Option<DeviceEvent> {
+        self.receiver.recv().ok()
+    }
+}
+
+/// Waits until a device is connected and opens it. If `udid` is `None`, any device is accepted.
+/// Fails with `IDEVICE_E_TIMEOUT` if no matching device appears within `timeout`.
+///
+/// ```rust,no_run
+/// use libimobiledevice::wait_for_device;
+/// use std::time::Duration;
+///
+/// let device = wait_for_device(None, Duration::from_secs(30)).unwrap();
+/// ```
+pub fn wait_for_device(udid: Option<&str>, timeout: Duration) -> Result<Device, idevice_error_t> {
+    let deadline = Instant::now() + timeout;
+    // Subscribe before listing, so a device connected in between is not missed.
+    let mut monitor = try!(DeviceMonitor::new());
+    let matches = |candidate: &str| udid.map_or(true, |udid| udid == candidate);
+
+    for candidate in try!(devices()) {
+        if matches(&candidate) {
+            return Device::new(&candidate);
+        }
+    }
+
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(IDEVICE_E_TIMEOUT);
+        }
+        if let Some(DeviceEvent::Added(candidate)) = monitor.next_timeout(deadline - now) {
+            if matches(&candidate) {
+                return Device::new(&candidate);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeviceEvent, register, unregister, dispatch, subscribe, trampoline};
+    use libimobiledevice_sys::*;
+    use std::ptr::null_mut;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_dispatch_to_all_subscribers() {
+        let (tx1, rx1) = channel();
+        let (tx2, rx2) = channel();
+        // Other tests dispatch concurrently, so only listen to our own UDID.
+        let id1 = register(Box::new(move |e: DeviceEvent| if e.udid() == "abc" { tx1.send(e).unwrap() }));
+        let id2 = register(Box::new(move |e: DeviceEvent| if e.udid() == "abc" { tx2.send(e).unwrap() }));
+
+        dispatch(DeviceEvent::Added("abc".to_owned()));
+        unregister(id1);
+        dispatch(DeviceEvent::Removed("abc".to_owned()));
+        unregister(id2);
+
+        assert_eq!(rx1.try_iter().collect::<Vec<_>>(), vec![DeviceEvent::Added("abc".to_owned())]);
+        assert_eq!(rx2.try_iter().collect::<Vec<_>>(), vec![
+            DeviceEvent::Added("abc".to_owned()),
+            DeviceEvent::Removed("abc".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn test_panicking_subscriber_is_isolated() {
+        let (tx, rx) = channel();
+        let id1 = register(Box::new(|e: DeviceEvent| if e.udid() == "xyz" { panic!("subscriber failure") }));
+        let id2 = register(Box::new(move |e: DeviceEvent| if e.udid() == "xyz" { tx.send(e).unwrap() }));
+
+        dispatch(DeviceEvent::Added("xyz".to_owned()));
+        unregister(id1);
+        unregister(id2);
+
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![DeviceEvent::Added("xyz".to_owned())]);
+    }
+
+    #[test]
+    fn test_trampoline_ignores_unknown_events() {
+        let (tx, rx) = channel();
+        let id = register(Box::new(move |e: DeviceEvent| if e.udid() == "tramp" { tx.send(e).unwrap() }));
+        for &event in &[IDEVICE_DEVICE_ADD, IDEVICE_DEVICE_PAIRED, IDEVICE_DEVICE_REMOVE] {
+            let raw = idevice_event_t {
+                event: event,
+                udid: b"tramp\0".as_ptr() as *const _,
+                conn_type: 1,
+            };
+            unsafe { trampoline(&raw, null_mut()) };
+        }
+        unregister(id);
+
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![
+            DeviceEvent::Added("tramp".to_owned()),
+            DeviceEvent::Removed("tramp".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn test_subscribe_inside_callback() {
+        let (tx, rx) = channel();
+        let id = register(Box::new(move |e: DeviceEvent| if e.udid() == "nested" {
+            // Neither call may touch the C subscription from the event thread.
+            let guard = subscribe(|_| {}).unwrap();
+            drop(guard);
+            tx.send(()).unwrap();
+        }));
+
+        dispatch(DeviceEvent::Added("nested".to_owned()));
+        unregister(id);
+
+        assert_eq!(rx.try_iter().count(), 1);
+    }
+}
@@ -0,0 +1,127 @@
+//! Typed snapshot of the standard device properties.
+
+use libimobiledevice_sys::lockdown::*;
+use libplist::{DictNode, FromPlistNode};
+use libplist::c_str::ToCStr;
+
+use lockdown::LockdownClient;
+
+/// Common properties of a device, read from the default lockdownd domain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// The user-assigned name, e.g. "John's iPhone".
+    pub device_name: String,
+    /// The device family, e.g. "iPhone" or "iPad".
+    pub device_class: String,
+    /// The model identifier, e.g. "iPhone9,3".
+    pub product_type: String,
+    /// The OS version, e.g. "10.3.1".
+    pub product_version: String,
+    /// The OS build, e.g. "14E304".
+    pub build_version: String,
+    /// The hardware serial number.
+    pub serial_number: String,
+    /// The UDID.
+    pub unique_device_id: String,
+    /// The ECID.
+    pub unique_chip_id: u64,
+    /// The board identifier, e.g. "D101AP".
+    pub hardware_model: Option<String>,
+    /// The CPU architecture, e.g. "arm64".
+    pub cpu_architecture: Option<String>,
+    /// The Wi-Fi MAC address.
+    pub wifi_address: Option<String>,
+    /// The Bluetooth MAC address.
+    pub bluetooth_address: Option<String>,
+    /// The phone number of the SIM card, if any.
+    pub phone_number: Option<String>,
+}
+
+impl DeviceInfo {
+    /// Reads all properties in one request. The client must have completed the handshake.
+    ///
+    /// A missing required property is reported as `LOCKDOWN_E_MISSING_VALUE`, and a property of
+    /// the wrong type as `LOCKDOWN_E_PLIST_ERROR`.
+    pub fn query(client: &LockdownClient) -> Result<DeviceInfo, lockdownd_error_t> {
+        let node = try!(client.get_value(None, None));
+        let dict = try!(node.dict().map_err(|_| LOCKDOWN_E_PLIST_ERROR));
+        DeviceInfo::from_dict(dict)
+    }
+
+    fn from_dict(dict: &DictNode) -> Result<DeviceInfo, lockdownd_error_t> {
+        Ok(DeviceInfo {
+            device_name: try!(required(dict, "DeviceName")),
+            device_class: try!(required(dict, "DeviceClass")),
+            product_type: try!(required(dict, "ProductType")),
+            product_version: try!(required(dict, "ProductVersion")),
+            build_version: try!(required(dict, "BuildVersion")),
+            serial_number: try!(required(dict, "SerialNumber")),
+            unique_device_id: try!(required(dict, "UniqueDeviceID")),
+            unique_chip_id: try!(required(dict, "UniqueChipID")),
+            hardware_model: try!(optional(dict, "HardwareModel")),
+            cpu_architecture: try!(optional(dict, "CPUArchitecture")),
+            wifi_address: try!(optional(dict, "WiFiAddress")),
+            bluetooth_address: try!(optional(dict, "BluetoothAddress")),
+            phone_number: try!(optional(dict, "PhoneNumber")),
+        })
+    }
+}
+
+fn optional<T: FromPlistNode>(dict: &DictNode, key: &str) -> Result<Option<T>, lockdownd_error_t> {
+    let key = key.to_c_str().unwrap();
+    match dict.get(&key) {
+        Some(node) => T::from_plist_node(node).map(Some).map_err(|_| LOCKDOWN_E_PLIST_ERROR),
+        None => Ok(None),
+    }
+}
+
+fn required<T: FromPlistNode>(dict: &DictNode, key: &str) -> Result<T, lockdownd_error_t> {
+    try!(optional(dict, key)).ok_or(LOCKDOWN_E_MISSING_VALUE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeviceInfo;
+    use libimobiledevice_sys::lockdown::*;
+    use libplist::OwnedNode;
+
+    fn sample() -> OwnedNode {
+        let mut node = OwnedNode::new_dict();
+        {
+            let dict = node.dict_mut().unwrap();
+            for &(key, value) in &[
+                (const_cstr!("DeviceName"), const_cstr!("Test iPhone")),
+                (const_cstr!("DeviceClass"), const_cstr!("iPhone")),
+                (const_cstr!("ProductType"), const_cstr!("iPhone9,3")),
+                (const_cstr!("ProductVersion"), const_cstr!("10.3.1")),
+                (const_cstr!("BuildVersion"), const_cstr!("14E304")),
+                (const_cstr!("SerialNumber"), const_cstr!("C00000000000")),
+                (const_cstr!("UniqueDeviceID"), const_cstr!("0123456789abcdef0123456789abcdef01234567")),
+                (const_cstr!("WiFiAddress"), const_cstr!("00:11:22:33:44:55")),
+            ] {
+                dict.insert(key.as_cstr(), OwnedNode::new_str(value.as_cstr()));
+            }
+            dict.insert(const_cstr!("UniqueChipID").as_cstr(), OwnedNode::new_uint(0x1234));
+        }
+        node
+    }
+
+    #[test]
+    fn test_from_dict() {
+        let node = sample();
+        let info = DeviceInfo::from_dict(node.dict().unwrap()).unwrap();
+        assert_eq!(info.product_type, "iPhone9,3");
+        assert_eq!(info.unique_chip_id, 0x1234);
+        assert_eq!(info.wifi_address, Some("00:11:22:33:44:55".to_owned()));
+        assert_eq!(info.bluetooth_address, None);
+    }
+
+    #[test]
+    fn test_missing_and_mistyped() {
+        let mut node = sample();
+        node.dict_mut().unwrap().insert(const_cstr!("UniqueChipID").as_cstr(), OwnedNode::new_bool(true));
+        assert_eq!(DeviceInfo::from_dict(node.dict().unwrap()).err(), Some(LOCKDOWN_E_PLIST_ERROR));
+        node.dict_mut().unwrap().remove(const_cstr!("DeviceName").as_cstr());
+        assert_eq!(DeviceInfo::from_dict(node.dict().unwrap()).err(), Some(LOCKDOWN_E_MISSING_VALUE));
+    }
+}
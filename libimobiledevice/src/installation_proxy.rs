@@ -0,0 +1,688 @@
+//! Client of the installation proxy service, which manages the apps installed on a device.
+
+use libimobiledevice_sys::installation_proxy::*;
+use libplist::{OwnedNode, Node, DictNode, FromPlistNode, PlistError};
+use libplist::c_str::ToCStr;
+use libplist::node::BorrowedNode;
+use libplist_sys::plist_t;
+use libc::{c_char, c_void};
+
+use std::error;
+use std::fmt;
+use std::ptr::null_mut;
+use std::sync::Mutex;
+use std::sync::mpsc::{channel, Sender};
+use std::time::Duration;
+#[cfg(feature="afc")] use std::fs;
+#[cfg(feature="afc")] use std::io;
+#[cfg(feature="afc")] use std::path::Path;
+
+use device::Device;
+use lockdown::ServiceDescriptor;
+use internal::{ToResult, to_label, take_string};
+#[cfg(feature="afc")] use afc::{AfcClient, TransferProgress, to_io_error};
+
+/// Which apps `AppManager::list` returns.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AppKind {
+    /// Apps installed by the user.
+    User,
+    /// Apps shipped with the OS.
+    System,
+    /// All apps.
+    Any,
+}
+
+impl AppKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            AppKind::User => "User",
+            AppKind::System => "System",
+            AppKind::Any => "Any",
+        }
+    }
+}
+
+/// The attributes of an installed app read by `AppInfo`.
+const RETURN_ATTRIBUTES: &'static [&'static str] = &[
+    "CFBundleIdentifier",
+    "CFBundleVersion",
+    "CFBundleShortVersionString",
+    "CFBundleDisplayName",
+    "CFBundleName",
+    "ApplicationType",
+    "Path",
+    "Container",
+    "Entitlements",
+];
+
+/// Information about an installed app.
+#[derive(Debug)]
+pub struct AppInfo {
+    /// The bundle identifier, e.g. "com.apple.mobilesafari".
+    pub bundle_identifier: String,
+    /// The build version (`CFBundleVersion`).
+    pub bundle_version: Option<String>,
+    /// The marketing version (`CFBundleShortVersionString`).
+    pub short_version: Option<String>,
+    /// The name shown on the home screen, falling back to `CFBundleName`.
+    pub display_name: Option<String>,
+    /// "User", "System" or another kind reported by the device.
+    pub application_type: Option<String>,
+    /// The path of the `.app` bundle on the device.
+    pub path: Option<String>,
+    /// The path of the data container on the device, for user apps.
+    pub container: Option<String>,
+    /// The entitlements the app is signed with.
+    pub entitlements: Option<OwnedNode>,
+}
+
+impl FromPlistNode for AppInfo {
+    /// Reads an app description returned by the installation proxy. A dictionary without
+    /// `CFBundleIdentifier` is rejected as an unsupported type.
+    fn from_plist_node(node: &Node) -> Result<AppInfo, PlistError> {
+        let dict = try!(node.dict());
+        let bundle_identifier = match try!(field(dict, "CFBundleIdentifier")) {
+            Some(id) => id,
+            None => return Err(PlistError::UnsupportedType(node.node_type())),
+        };
+        let display_name = match try!(field(dict, "CFBundleDisplayName")) {
+            Some(name) => Some(name),
+            None => try!(field(dict, "CFBundleName")),
+        };
+        Ok(AppInfo {
+            bundle_identifier: bundle_identifier,
+            bundle_version: try!(field(dict, "CFBundleVersion")),
+            short_version: try!(field(dict, "CFBundleShortVersionString")),
+            display_name: display_name,
+            application_type: try!(field(dict, "ApplicationType")),
+            path: try!(field(dict, "Path")),
+            container: try!(field(dict, "Container")),
+            entitlements: try!(field(dict, "Entitlements")),
+        })
+    }
+}
+
+fn field<T: FromPlistNode>(dict: &DictNode, key: &str) -> Result<Option<T>, PlistError> {
+    let key = key.to_c_str().unwrap();
+    match dict.get(&key) {
+        Some(node) => T::from_plist_node(node).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// The progress of an operation, as reported by the device.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OperationStatus {
+    /// The current stage, e.g. "VerifyingApplication".
+    pub name: String,
+    /// The overall progress in percent, if reported.
+    pub percent: Option<u32>,
+}
+
+/// A status update sent from the callback thread.
+#[derive(Debug)]
+enum Status {
+    Progress(OperationStatus),
+    Complete,
+    Failed(OperationError),
+}
+
+/// Reads a status update without an error.
+fn parse_status(node: &Node) -> Status {
+    let dict = match node.dict() {
+        Ok(dict) => dict,
+        Err(_) => return Status::Progress(OperationStatus { name: String::new(), percent: None }),
+    };
+    let name = field::<String>(dict, "Status").ok().and_then(|name| name).unwrap_or_default();
+    if name == "Complete" {
+        return Status::Complete;
+    }
+    Status::Progress(OperationStatus {
+        name: name,
+        percent: field::<u64>(dict, "PercentComplete").ok().and_then(|p| p).map(|p| p as u32),
+    })
+}
+
+/// Copies a malloc'ed C string which may be NULL, and frees the original.
+unsafe fn take_opt_string(ptr: *mut c_char) -> Option<String> {
+    if ptr.is_null() { None } else { Some(take_string(ptr)) }
+}
+
+type StatusSlot = Mutex<Option<Sender<Status>>>;
+
+unsafe extern "C" fn status_callback(_: plist_t, status: plist_t, user_data: *mut c_void) {
+    let mut name = null_mut();
+    let mut description = null_mut();
+    let mut detail = 0;
+    let code = instproxy_status_get_error(status, &mut name, &mut description, &mut detail);
+    let update = if code == INSTPROXY_E_SUCCESS {
+        parse_status(Node::from_ptr(status))
+    } else {
+        Status::Failed(OperationError::Failed {
+            code: code,
+            name: take_opt_string(name).unwrap_or_default(),
+            description: take_opt_string(description),
+            detail: if detail != 0 { Some(detail) } else { None },
+        })
+    };
+    let slot = &*(user_data as *const StatusSlot);
+    if let Ok(sender) = slot.lock() {
+        if let Some(ref sender) = *sender {
+            let _ = sender.send(update);
+        }
+    }
+}
+
+/// How long an operation may go without any status update before it is considered failed. The
+/// C library silently stops reporting if the connection breaks.
+const STATUS_TIMEOUT_SECS: u64 = 60;
+
+/// The error of an operation which reports its progress.
+#[derive(Debug)]
+pub enum OperationError {
+    /// Uploading the package to the device failed.
+    #[cfg(feature="afc")]
+    Upload(io::Error),
+    /// The request could not be made, or no status arrived in time (`INSTPROXY_E_RECEIVE_TIMEOUT`).
+    Proxy(instproxy_error_t),
+    /// The device reported a failure.
+    Failed {
+        /// The error code corresponding to `name`.
+        code: instproxy_error_t,
+        /// The error name, e.g. "ApplicationVerificationFailed".
+        name: String,
+        /// A human readable description, if any.
+        description: Option<String>,
+        /// An additional numeric error code, if any.
+        detail: Option<u64>,
+    },
+}
+
+impl OperationError {
+    /// The error code of the installation proxy, or `INSTPROXY_E_UNKNOWN_ERROR` for upload
+    /// failures.
+    pub fn code(&self) -> instproxy_error_t {
+        match *self {
+            #[cfg(feature="afc")]
+            OperationError::Upload(_) => INSTPROXY_E_UNKNOWN_ERROR,
+            OperationError::Proxy(code) | OperationError::Failed { code, .. } => code,
+        }
+    }
+}
+
+impl error::Error for OperationError {
+    fn description(&self) -> &str {
+        match *self {
+            #[cfg(feature="afc")]
+            OperationError::Upload(_) => "package upload failed",
+            OperationError::Proxy(_) => "installation proxy error",
+            OperationError::Failed { .. } => "operation failed on the device",
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            #[cfg(feature="afc")]
+            OperationError::Upload(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OperationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            #[cfg(feature="afc")]
+            OperationError::Upload(ref e) => write!(formatter, "package upload failed: {}", e),
+            OperationError::Proxy(e) => write!(formatter, "installation proxy error: {:?}", e),
+            OperationError::Failed { ref name, ref description, .. } => {
+                try!(write!(formatter, "operation failed on the device: {}", name));
+                match *description {
+                    Some(ref description) => write!(formatter, " ({})", description),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl From<instproxy_error_t> for OperationError {
+    fn from(e: instproxy_error_t) -> OperationError {
+        OperationError::Proxy(e)
+    }
+}
+
+/// The package installed by `AppManager::install`.
+#[derive(Copy, Clone, Debug)]
+pub enum Package<'a> {
+    /// A package already on the device, given as a path in the AFC media directory, e.g.
+    /// "PublicStaging/Example.ipa".
+    Staged(&'a str),
+    /// A local `.ipa` file or `.app` directory, uploaded to `PublicStaging` first.
+    #[cfg(feature="afc")]
+    Local(&'a AfcClient, &'a Path),
+}
+
+/// The directory of the AFC media directory where packages are uploaded before installation.
+#[cfg(feature="afc")]
+const STAGING_DIR: &'static str = "PublicStaging";
+
+/// Options of `AppManager::install`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InstallOptions {
+    upgrade: bool,
+    developer: bool,
+    bundle_identifier: Option<String>,
+}
+
+impl InstallOptions {
+    pub fn new() -> InstallOptions {
+        InstallOptions::default()
+    }
+
+    /// Upgrades an installed app, keeping its data (`instproxy_upgrade`).
+    pub fn upgrade(mut self, upgrade: bool) -> InstallOptions {
+        self.upgrade = upgrade;
+        self
+    }
+
+    /// Installs an unpacked `.app` bundle signed for development. Always set when a local
+    /// directory is uploaded.
+    pub fn developer(mut self, developer: bool) -> InstallOptions {
+        self.developer = developer;
+        self
+    }
+
+    /// Declares the bundle identifier of the package.
+    pub fn bundle_identifier(mut self, bundle_identifier: &str) -> InstallOptions {
+        self.bundle_identifier = Some(bundle_identifier.to_owned());
+        self
+    }
+
+    fn to_node(&self) -> Result<OwnedNode, instproxy_error_t> {
+        let mut options = OwnedNode::new_dict();
+        {
+            let dict = options.dict_mut().unwrap();
+            if self.developer {
+                dict.insert(&"PackageType".to_c_str().unwrap(), OwnedNode::new_str(&"Developer".to_c_str().unwrap()));
+            }
+            if let Some(ref id) = self.bundle_identifier {
+                let id = try!(id.to_c_str().map_err(|_| INSTPROXY_E_INVALID_ARG));
+                dict.insert(&"CFBundleIdentifier".to_c_str().unwrap(), OwnedNode::new_str(&id));
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// What `AppManager::archive` stores.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ArchiveContents {
+    /// The app and its documents.
+    All,
+    /// Only the app.
+    ApplicationOnly,
+    /// Only the documents.
+    DocumentsOnly,
+}
+
+impl Default for ArchiveContents {
+    fn default() -> ArchiveContents {
+        ArchiveContents::All
+    }
+}
+
+/// Options of `AppManager::archive`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ArchiveOptions {
+    uninstall: bool,
+    contents: ArchiveContents,
+}
+
+impl ArchiveOptions {
+    pub fn new() -> ArchiveOptions {
+        ArchiveOptions::default()
+    }
+
+    /// Uninstalls the app after archiving it. By default the app is kept.
+    pub fn uninstall(mut self, uninstall: bool) -> ArchiveOptions {
+        self.uninstall = uninstall;
+        self
+    }
+
+    /// Chooses what to archive. By default both the app and its documents are archived.
+    pub fn contents(mut self, contents: ArchiveContents) -> ArchiveOptions {
+        self.contents = contents;
+        self
+    }
+
+    fn to_node(&self) -> OwnedNode {
+        let mut options = OwnedNode::new_dict();
+        {
+            let dict = options.dict_mut().unwrap();
+            dict.insert(&"SkipUninstall".to_c_str().unwrap(), OwnedNode::new_bool(!self.uninstall));
+            let archive_type = match self.contents {
+                ArchiveContents::All => None,
+                ArchiveContents::ApplicationOnly => Some("ApplicationOnly"),
+                ArchiveContents::DocumentsOnly => Some("DocumentsOnly"),
+            };
+            if let Some(archive_type) = archive_type {
+                dict.insert(&"ArchiveType".to_c_str().unwrap(), OwnedNode::new_str(&archive_type.to_c_str().unwrap()));
+            }
+        }
+        options
+    }
+}
+
+/// An operation on an installed app or its archive, taking the bundle identifier.
+type AppOperation = unsafe extern "C" fn(instproxy_client_t, *const c_char, plist_t, instproxy_status_cb_t, *mut c_void) -> instproxy_error_t;
+
+/// An event reported by `AppManager::install`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InstallProgress {
+    /// Part of a local package has been uploaded.
+    #[cfg(feature="afc")]
+    Uploading(TransferProgress),
+    /// The device is installing the package.
+    Installing(OperationStatus),
+}
+
+/// An owned installation proxy client, for listing and managing apps. The client is freed when
+/// dropped.
+pub struct AppManager {
+    client: instproxy_client_t,
+    // Receives the status updates of the running operation. Boxed so its address stays valid for
+    // the callback thread, which `instproxy_client_free` joins.
+    status: Box<StatusSlot>,
+}
+
+unsafe impl Send for AppManager {}
+
+impl AppManager {
+    pub unsafe fn from_ptr(client: instproxy_client_t) -> AppManager {
+        AppManager {
+            client: client,
+            status: Box::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn as_ptr(&self) -> instproxy_client_t {
+        self.client
+    }
+
+    /// Connects to an installation proxy service started by `LockdownClient::start_service`.
+    pub fn new(device: &Device, service: &ServiceDescriptor) -> Result<AppManager, instproxy_error_t> {
+        let mut client = null_mut();
+        unsafe {
+            try!(instproxy_client_new(device.as_ptr(), service.as_ptr(), &mut client).to_result());
+            Ok(AppManager::from_ptr(client))
+        }
+    }
+
+    /// Starts the installation proxy service through lockdownd and connects to it. If `label` is
+    /// `None`, the name of the current executable is used.
+    pub fn start_service(device: &Device, label: Option<&str>) -> Result<AppManager, instproxy_error_t> {
+        let label = try!(to_label(label).map_err(|_| INSTPROXY_E_INVALID_ARG));
+        let mut client = null_mut();
+        unsafe {
+            try!(instproxy_client_start_service(device.as_ptr(), &mut client, label.as_ptr()).to_result());
+            Ok(AppManager::from_ptr(client))
+        }
+    }
+
+    /// Lists the installed apps of the given kind. An app description which cannot be read is
+    /// reported as `INSTPROXY_E_PLIST_ERROR`.
+    ///
+    /// ```rust,no_run
+    /// use libimobiledevice::{Device, AppManager, AppKind};
+    ///
+    /// let device = Device::any().unwrap();
+    /// let manager = AppManager::start_service(&device, None).unwrap();
+    /// for app in manager.list(AppKind::User).unwrap() {
+    ///     println!("{} {:?}", app.bundle_identifier, app.short_version);
+    /// }
+    /// ```
+    pub fn list(&self, kind: AppKind) -> Result<Vec<AppInfo>, instproxy_error_t> {
+        let options = list_options(kind);
+        let mut result = null_mut();
+        unsafe {
+            try!(instproxy_browse(self.client, options.as_ptr(), &mut result).to_result());
+            let result = try!(OwnedNode::try_from_ptr(result).ok_or(INSTPROXY_E_PLIST_ERROR));
+            Vec::from_plist_node(&result).map_err(|_| INSTPROXY_E_PLIST_ERROR)
+        }
+    }
+
+    /// Installs or upgrades an app, blocking until the device is done. A local package is first
+    /// uploaded to `PublicStaging`, where it is left afterwards.
+    ///
+    /// ```rust,no_run
+    /// use libimobiledevice::{Device, LockdownClient, AfcClient, AppManager};
+    /// use libimobiledevice::installation_proxy::{Package, InstallOptions, InstallProgress};
+    /// use std::path::Path;
+    ///
+    /// let device = Device::any().unwrap();
+    /// let lockdown = LockdownClient::new_with_handshake(&device, None).unwrap();
+    /// let afc = AfcClient::new(&device, &lockdown.start_service("com.apple.afc").unwrap()).unwrap();
+    /// let manager = AppManager::start_service(&device, None).unwrap();
+    /// let package = Package::Local(&afc, Path::new("Example.ipa"));
+    /// manager.install(package, &InstallOptions::new().upgrade(true), |progress| {
+    ///     match progress {
+    ///         InstallProgress::Uploading(p) => println!("uploaded {} bytes", p.bytes),
+    ///         InstallProgress::Installing(s) => println!("{} {:?}%", s.name, s.percent),
+    ///     }
+    /// }).unwrap();
+    /// ```
+    pub fn install<F: FnMut(InstallProgress)>(&self, package: Package, options: &InstallOptions, mut progress: F) -> Result<(), OperationError> {
+        let (path, is_dir) = match package {
+            Package::Staged(path) => (path.to_owned(), false),
+            #[cfg(feature="afc")]
+            Package::Local(afc, local) => try!(upload(afc, local, &mut progress).map_err(OperationError::Upload)),
+        };
+        let path = try!(path.to_c_str().map_err(|_| INSTPROXY_E_INVALID_ARG));
+        let node = try!(InstallOptions { developer: options.developer || is_dir, ..options.clone() }.to_node());
+        let start = if options.upgrade { instproxy_upgrade } else { instproxy_install };
+        self.run(|cb, user_data| unsafe {
+            start(self.client, path.as_ptr(), node.as_ptr(), cb, user_data)
+        }, &mut |status| progress(InstallProgress::Installing(status)))
+    }
+
+    /// Uninstalls an app, blocking until the device is done.
+    pub fn uninstall<F: FnMut(OperationStatus)>(&self, bundle_id: &str, mut progress: F) -> Result<(), OperationError> {
+        self.run_for_app(instproxy_uninstall, bundle_id, OwnedNode::new_dict(), &mut progress)
+    }
+
+    /// Archives an app on the device, blocking until the device is done. The archive replaces any
+    /// previous archive of the app.
+    ///
+    /// ```rust,no_run
+    /// use libimobiledevice::{Device, AppManager};
+    /// use libimobiledevice::installation_proxy::{ArchiveOptions, ArchiveContents};
+    ///
+    /// let device = Device::any().unwrap();
+    /// let manager = AppManager::start_service(&device, None).unwrap();
+    /// let options = ArchiveOptions::new().contents(ArchiveContents::DocumentsOnly);
+    /// manager.archive("com.example.app", &options, |status| println!("{} {:?}%", status.name, status.percent)).unwrap();
+    /// ```
+    pub fn archive<F: FnMut(OperationStatus)>(&self, bundle_id: &str, options: &ArchiveOptions, mut progress: F) -> Result<(), OperationError> {
+        self.run_for_app(instproxy_archive, bundle_id, options.to_node(), &mut progress)
+    }
+
+    /// Reinstalls an app from its archive, blocking until the device is done.
+    pub fn restore_archive<F: FnMut(OperationStatus)>(&self, bundle_id: &str, mut progress: F) -> Result<(), OperationError> {
+        self.run_for_app(instproxy_restore, bundle_id, OwnedNode::new_dict(), &mut progress)
+    }
+
+    /// Deletes the archive of an app, blocking until the device is done.
+    pub fn remove_archive<F: FnMut(OperationStatus)>(&self, bundle_id: &str, mut progress: F) -> Result<(), OperationError> {
+        self.run_for_app(instproxy_remove_archive, bundle_id, OwnedNode::new_dict(), &mut progress)
+    }
+
+    /// Lists the bundle identifiers of the archived apps.
+    pub fn archives(&self) -> Result<Vec<String>, instproxy_error_t> {
+        let mut result = null_mut();
+        unsafe {
+            try!(instproxy_lookup_archives(self.client, null_mut(), &mut result).to_result());
+            let result = try!(OwnedNode::try_from_ptr(result).ok_or(INSTPROXY_E_PLIST_ERROR));
+            let dict = try!(result.dict().map_err(|_| INSTPROXY_E_PLIST_ERROR));
+            Ok(dict.iter().map(|(key, _)| (*key).to_owned()).collect())
+        }
+    }
+
+    fn run_for_app(&self, operation: AppOperation, bundle_id: &str, options: OwnedNode, progress: &mut dyn FnMut(OperationStatus)) -> Result<(), OperationError> {
+        let bundle_id = try!(bundle_id.to_c_str().map_err(|_| INSTPROXY_E_INVALID_ARG));
+        self.run(|cb, user_data| unsafe {
+            operation(self.client, bundle_id.as_ptr(), options.as_ptr(), cb, user_data)
+        }, progress)
+    }
+
+    /// Starts an asynchronous operation with `start`, and reports its status updates to
+    /// `progress` until it completes.
+    fn run<S>(&self, start: S, progress: &mut dyn FnMut(OperationStatus)) -> Result<(), OperationError>
+        where S: FnOnce(instproxy_status_cb_t, *mut c_void) -> instproxy_error_t
+    {
+        let (sender, receiver) = channel();
+        *self.status.lock().unwrap() = Some(sender);
+        let user_data = &*self.status as *const StatusSlot as *mut c_void;
+        if let Err(e) = start(Some(status_callback), user_data).to_result() {
+            *self.status.lock().unwrap() = None;
+            return Err(OperationError::Proxy(e));
+        }
+        let result = loop {
+            match receiver.recv_timeout(Duration::from_secs(STATUS_TIMEOUT_SECS)) {
+                Ok(Status::Progress(status)) => progress(status),
+                Ok(Status::Complete) => break Ok(()),
+                Ok(Status::Failed(e)) => break Err(e),
+                Err(_) => break Err(OperationError::Proxy(INSTPROXY_E_RECEIVE_TIMEOUT)),
+            }
+        };
+        *self.status.lock().unwrap() = None;
+        result
+    }
+}
+
+/// Uploads a local package to the staging directory. Returns its path on the device, and whether
+/// it is a directory.
+#[cfg(feature="afc")]
+fn upload<F: FnMut(InstallProgress)>(afc: &AfcClient, local: &Path, progress: &mut F) -> io::Result<(String, bool)> {
+    let name = try!(local.file_name().and_then(|name| name.to_str()).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "package file name is not valid UTF-8")
+    }));
+    let remote = format!("{}/{}", STAGING_DIR, name);
+    try!(afc.create_dir_all(STAGING_DIR).map_err(to_io_error));
+    let mut report = |p: &TransferProgress| progress(InstallProgress::Uploading(*p));
+    let is_dir = try!(fs::metadata(local)).is_dir();
+    if is_dir {
+        try!(afc.upload_dir(local, &remote, &mut report));
+    } else {
+        try!(afc.upload_file(local, &remote, &mut report));
+    }
+    Ok((remote, is_dir))
+}
+
+fn list_options(kind: AppKind) -> OwnedNode {
+    let mut options = OwnedNode::new_dict();
+    {
+        let dict = options.dict_mut().unwrap();
+        dict.insert(&"ApplicationType".to_c_str().unwrap(), OwnedNode::new_str(&kind.as_str().to_c_str().unwrap()));
+        let attributes = RETURN_ATTRIBUTES.iter().map(|a| OwnedNode::new_str(&a.to_c_str().unwrap())).collect();
+        dict.insert(&"ReturnAttributes".to_c_str().unwrap(), attributes);
+    }
+    options
+}
+
+impl Drop for AppManager {
+    fn drop(&mut self) {
+        // Also waits for the callback thread, before `status` is freed.
+        unsafe { instproxy_client_free(self.client) };
+    }
+}
+
+impl fmt::Debug for AppManager {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_tuple("AppManager").field(&self.client).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AppInfo, AppKind, ArchiveContents, ArchiveOptions, InstallOptions, OperationStatus, Status, list_options, parse_status};
+    use libplist::{OwnedNode, FromPlistNode};
+
+    #[test]
+    fn test_app_info() {
+        let node = OwnedNode::from_xml("<plist><dict>
+            <key>CFBundleIdentifier</key><string>com.example.app</string>
+            <key>CFBundleVersion</key><string>42</string>
+            <key>CFBundleName</key><string>Example</string>
+            <key>ApplicationType</key><string>User</string>
+            <key>Entitlements</key><dict><key>get-task-allow</key><true/></dict>
+        </dict></plist>").unwrap();
+        let info = AppInfo::from_plist_node(&node).unwrap();
+        assert_eq!(info.bundle_identifier, "com.example.app");
+        assert_eq!(info.bundle_version, Some("42".to_owned()));
+        assert_eq!(info.short_version, None);
+        assert_eq!(info.display_name, Some("Example".to_owned()));
+        assert!(info.entitlements.unwrap().dict().unwrap().len() == 1);
+    }
+
+    #[test]
+    fn test_app_info_invalid() {
+        let missing_id = OwnedNode::from_xml("<plist><dict><key>Path</key><string>/</string></dict></plist>").unwrap();
+        assert!(AppInfo::from_plist_node(&missing_id).is_err());
+        let mistyped = OwnedNode::from_xml("<plist><dict><key>CFBundleIdentifier</key><integer>1</integer></dict></plist>").unwrap();
+        assert!(AppInfo::from_plist_node(&mistyped).is_err());
+    }
+
+    #[test]
+    fn test_list_options() {
+        let options = list_options(AppKind::System);
+        let dict = options.dict().unwrap();
+        assert_eq!(dict.get(const_cstr!("ApplicationType").as_cstr()).unwrap().string_lossy().unwrap(), "System");
+        assert_eq!(dict.get(const_cstr!("ReturnAttributes").as_cstr()).unwrap().array().unwrap().len(), 9);
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let node = OwnedNode::from_xml("<plist><dict>
+            <key>Status</key><string>VerifyingApplication</string>
+            <key>PercentComplete</key><integer>40</integer>
+        </dict></plist>").unwrap();
+        match parse_status(&node) {
+            Status::Progress(status) => assert_eq!(status, OperationStatus {
+                name: "VerifyingApplication".to_owned(),
+                percent: Some(40),
+            }),
+            s => panic!("unexpected {:?}", s),
+        }
+
+        let node = OwnedNode::from_xml("<plist><dict><key>Status</key><string>Complete</string></dict></plist>").unwrap();
+        match parse_status(&node) {
+            Status::Complete => {}
+            s => panic!("unexpected {:?}", s),
+        }
+    }
+
+    #[test]
+    fn test_install_options() {
+        let options = InstallOptions::new().developer(true).bundle_identifier("com.example.app").to_node().unwrap();
+        let dict = options.dict().unwrap();
+        assert_eq!(dict.get(const_cstr!("PackageType").as_cstr()).unwrap().string_lossy().unwrap(), "Developer");
+        assert_eq!(dict.get(const_cstr!("CFBundleIdentifier").as_cstr()).unwrap().string_lossy().unwrap(), "com.example.app");
+        assert_eq!(InstallOptions::new().upgrade(true).to_node().unwrap().dict().unwrap().len(), 0);
+        assert!(InstallOptions::new().bundle_identifier("a\0b").to_node().is_err());
+    }
+
+    #[test]
+    fn test_archive_options() {
+        let options = ArchiveOptions::new().to_node();
+        let dict = options.dict().unwrap();
+        assert_eq!(dict.len(), 1);
+        assert_eq!(bool::from_plist_node(dict.get(const_cstr!("SkipUninstall").as_cstr()).unwrap()).unwrap(), true);
+
+        let options = ArchiveOptions::new().uninstall(true).contents(ArchiveContents::ApplicationOnly).to_node();
+        let dict = options.dict().unwrap();
+        assert_eq!(bool::from_plist_node(dict.get(const_cstr!("SkipUninstall").as_cstr()).unwrap()).unwrap(), false);
+        assert_eq!(dict.get(const_cstr!("ArchiveType").as_cstr()).unwrap().string_lossy().unwrap(), "ApplicationOnly");
+    }
+}
@@ -0,0 +1,128 @@
+use libimobiledevice_sys::*;
+use libimobiledevice_sys::lockdown::*;
+#[cfg(feature="afc")] use libimobiledevice_sys::afc::*;
+#[cfg(feature="diagnostics-relay")] use libimobiledevice_sys::diagnostics_relay::*;
+#[cfg(feature="syslog-relay")] use libimobiledevice_sys::syslog_relay::*;
+#[cfg(feature="installation-proxy")] use libimobiledevice_sys::installation_proxy::*;
+use libc::{c_char, c_uint, c_void, free};
+use libplist::c_str::{ToCStr, from_c_str_lossy};
+
+use std::borrow::Cow;
+use std::env;
+use std::ffi::{CStr, CString, NulError};
+use std::ptr::null;
+use std::time::Duration;
+use std::u32;
+
+/// Converts a status code returned by a C function into a `Result`.
+pub trait ToResult: Sized {
+    fn to_result(self) -> Result<(), Self>;
+}
+
+macro_rules! impl_to_result {
+    ($($ty:ty => $success:expr),*) => {
+        $(impl ToResult for $ty {
+            fn to_result(self) -> Result<(), Self> {
+                if self == $success {
+                    Ok(())
+                } else {
+                    Err(self)
+                }
+            }
+        })*
+    }
+}
+
+impl_to_result! {
+    idevice_error_t => IDEVICE_E_SUCCESS,
+    lockdownd_error_t => LOCKDOWN_E_SUCCESS
+}
+
+#[cfg(feature="afc")]
+impl_to_result! {
+    afc_error_t => AFC_E_SUCCESS
+}
+
+#[cfg(feature="diagnostics-relay")]
+impl_to_result! {
+    diagnostics_relay_error_t => DIAGNOSTICS_RELAY_E_SUCCESS
+}
+
+#[cfg(feature="syslog-relay")]
+impl_to_result! {
+    syslog_relay_error_t => SYSLOG_RELAY_E_SUCCESS
+}
+
+#[cfg(feature="installation-proxy")]
+impl_to_result! {
+    instproxy_error_t => INSTPROXY_E_SUCCESS
+}
+
+/// Limits the length of a buffer to what the C API accepts in one call.
+pub fn clamp_len(len: usize) -> u32 {
+    if len > u32::MAX as usize { u32::MAX } else { len as u32 }
+}
+
+/// Converts a timeout into the number of milliseconds expected by the C API, rounding up so a
+/// non-zero timeout never becomes zero (which means "wait forever").
+pub fn to_millis(timeout: Duration) -> c_uint {
+    let millis = timeout.as_secs().saturating_mul(1000).saturating_add((timeout.subsec_nanos() as u64 + 999_999) / 1_000_000);
+    if millis > c_uint::MAX as u64 { c_uint::MAX } else { millis as c_uint }
+}
+
+/// The client label used when none is given: the file name of the current executable without
+/// extension, falling back to the crate name.
+pub fn default_label() -> String {
+    env::current_exe().ok()
+        .and_then(|path| path.file_stem().and_then(|s| s.to_str()).map(|s| s.to_owned()))
+        .unwrap_or_else(|| "libimobiledevice-rust".to_owned())
+}
+
+/// Converts an optional client label into a C string, using `default_label` if absent.
+pub fn to_label(label: Option<&str>) -> Result<CString, NulError> {
+    match label {
+        Some(label) => CString::new(label),
+        None => CString::new(default_label()),
+    }
+}
+
+/// Converts an optional string argument into a C string, rejecting interior NULs.
+pub fn to_opt_c_str(s: Option<&str>) -> Result<Option<Cow<CStr>>, NulError> {
+    match s {
+        Some(s) => s.to_c_str().map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Returns the pointer of an optional C string, or NULL if absent.
+pub fn opt_ptr(s: &Option<Cow<CStr>>) -> *const c_char {
+    s.as_ref().map_or(null(), |s| s.as_ptr())
+}
+
+/// Copies a malloc'ed C string returned by a C function into a `String`, and frees the original.
+pub unsafe fn take_string(ptr: *mut c_char) -> String {
+    let result = from_c_str_lossy(ptr);
+    free(ptr as *mut c_void);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_label, to_label, to_opt_c_str, opt_ptr};
+
+    #[test]
+    fn test_to_label() {
+        assert_eq!(to_label(Some("ideviceinfo")).unwrap().to_str(), Ok("ideviceinfo"));
+        assert!(to_label(Some("a\0b")).is_err());
+        let label = default_label();
+        assert!(!label.is_empty());
+        assert_eq!(to_label(None).unwrap().to_str(), Ok(&*label));
+    }
+
+    #[test]
+    fn test_opt_c_str() {
+        assert!(opt_ptr(&to_opt_c_str(None).unwrap()).is_null());
+        assert_eq!(&**to_opt_c_str(Some("ProductVersion")).unwrap().as_ref().unwrap(), const_cstr!("ProductVersion").as_cstr());
+        assert!(to_opt_c_str(Some("a\0b")).is_err());
+    }
+}
@@ -0,0 +1,60 @@
+//! Safe, high-level bindings for libimobiledevice.
+//!
+//! The raw C API is exposed by the `libimobiledevice-sys` crate. This crate wraps the handles in
+//! owned Rust types which free their resources when dropped, and converts error codes into
+//! `Result`s.
+//!
+//! # Examples
+//!
+//! Opening the first connected device and printing its UDID.
+//!
+//! ```rust,no_run
+//! use libimobiledevice::Device;
+//!
+//! let device = Device::any().unwrap();
+//! println!("{}", device.udid().unwrap());
+//! ```
+//!
+//! Listing the UDIDs of all connected devices.
+//!
+//! ```rust,no_run
+//! for udid in libimobiledevice::devices().unwrap() {
+//!     println!("{}", udid);
+//! }
+//! ```
+
+extern crate libimobiledevice_sys;
+extern crate libplist;
+extern crate libplist_sys;
+extern crate libc;
+#[cfg(feature="log-bridge")] extern crate log;
+#[cfg(feature="log-bridge")] extern crate libusbmuxd_sys;
+
+#[cfg(test)] #[macro_use] extern crate const_cstr;
+
+mod internal;
+pub mod error;
+pub mod device;
+pub mod event;
+pub mod connection;
+pub mod lockdown;
+pub mod pairing;
+pub mod info;
+#[cfg(feature="afc")] pub mod afc;
+#[cfg(feature="diagnostics-relay")] pub mod diagnostics_relay;
+#[cfg(feature="syslog-relay")] pub mod syslog_relay;
+#[cfg(feature="installation-proxy")] pub mod installation_proxy;
+#[cfg(feature="log-bridge")] pub mod logging;
+
+pub use error::Error;
+pub use device::{Device, devices};
+#[cfg(feature="libimobiledevice-1-3")] pub use device::{devices_extended, ConnectionType, DeviceListEntry, LookupOptions};
+pub use connection::{Connection, SslSession};
+pub use event::{DeviceEvent, DeviceMonitor, SubscriptionGuard, subscribe, wait_for_device};
+pub use lockdown::{LockdownClient, ServiceDescriptor, SessionGuard, SetValueError};
+pub use pairing::{PairRecord, PairingPrompt, pair_interactive};
+pub use info::DeviceInfo;
+#[cfg(feature="afc")] pub use afc::{AfcClient, AfcFile, AfcLockGuard, AfcDirEntry, AfcDeviceInfo, Metadata, TransferProgress};
+#[cfg(feature="diagnostics-relay")] pub use diagnostics_relay::DiagnosticsRelayClient;
+#[cfg(feature="syslog-relay")] pub use syslog_relay::{SyslogRelayClient, SyslogStream, SyslogLine};
+#[cfg(feature="installation-proxy")] pub use installation_proxy::{AppManager, AppKind, AppInfo};
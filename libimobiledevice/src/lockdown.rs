@@ -0,0 +1,309 @@
+//! Client of the lockdownd service, which manages device properties, pairing and services.
+
+use libimobiledevice_sys::idevice_t;
+use libimobiledevice_sys::lockdown::*;
+use libplist::{OwnedNode, FromPlistNode, ToPlistNode};
+use libplist::c_str::{ToCStr, from_c_str_lossy};
+use libc::{c_char, c_void, free};
+
+use std::borrow::Cow;
+use std::ffi::{CStr, CString};
+use std::ptr::null_mut;
+use std::fmt;
+
+use device::Device;
+use error::Error;
+use internal::{ToResult, take_string, opt_ptr, to_label, to_opt_c_str};
+
+/// An owned lockdownd client. The client is freed when dropped.
+pub struct LockdownClient(lockdownd_client_t);
+
+unsafe impl Send for LockdownClient {}
+
+impl LockdownClient {
+    pub unsafe fn from_ptr(client: lockdownd_client_t) -> LockdownClient {
+        LockdownClient(client)
+    }
+
+    pub fn as_ptr(&self) -> lockdownd_client_t {
+        self.0
+    }
+
+    fn create(device: &Device,
+              label: Option<&str>,
+              constructor: unsafe extern "C" fn(idevice_t, *mut lockdownd_client_t, *const c_char) -> lockdownd_error_t)
+              -> Result<LockdownClient, lockdownd_error_t> {
+        let label = try!(to_label(label).map_err(|_| LOCKDOWN_E_INVALID_ARG));
+        let mut client = null_mut();
+        unsafe {
+            try!(constructor(device.as_ptr(), &mut client, label.as_ptr()).to_result());
+            Ok(LockdownClient::from_ptr(client))
+        }
+    }
+
+    /// Connects to lockdownd without performing the handshake. Only a few requests, like
+    /// `query_type`, are allowed on such client.
+    ///
+    /// The label identifies the client in the device logs. If `None`, the name of the current
+    /// executable is used, like the C tools do.
+    pub fn new(device: &Device, label: Option<&str>) -> Result<LockdownClient, lockdownd_error_t> {
+        LockdownClient::create(device, label, lockdownd_client_new)
+    }
+
+    /// Connects to lockdownd, validates the pairing and starts an SSL session. See `new` for the
+    /// meaning of `label`.
+    pub fn new_with_handshake(device: &Device, label: Option<&str>) -> Result<LockdownClient, lockdownd_error_t> {
+        LockdownClient::create(device, label, lockdownd_client_new_with_handshake)
+    }
+
+    /// Changes the label sent with subsequent requests.
+    pub fn set_label(&self, label: &str) -> Result<(), lockdownd_error_t> {
+        let label = try!(label.to_c_str().map_err(|_| LOCKDOWN_E_INVALID_ARG));
+        unsafe { lockdownd_client_set_label(self.0, label.as_ptr()) };
+        Ok(())
+    }
+
+    /// Queries the type of the service daemon, which should be `com.apple.mobile.lockdown`.
+    pub fn query_type(&self) -> Result<String, lockdownd_error_t> {
+        let mut type_ = null_mut();
+        unsafe {
+            try!(lockdownd_query_type(self.0, &mut type_).to_result());
+            Ok(take_string(type_))
+        }
+    }
+
+    /// Reads a value from lockdownd. If `key` is `None`, returns the whole domain as a dictionary.
+    /// If `domain` is `None`, reads from the default domain.
+    pub fn get_value(&self, domain: Option<&str>, key: Option<&str>) -> Result<OwnedNode, lockdownd_error_t> {
+        let domain = try!(to_opt_c_str(domain).map_err(|_| LOCKDOWN_E_INVALID_ARG));
+        let key = try!(to_opt_c_str(key).map_err(|_| LOCKDOWN_E_INVALID_ARG));
+        let mut value = null_mut();
+        unsafe {
+            try!(lockdownd_get_value(self.0, opt_ptr(&domain), opt_ptr(&key), &mut value).to_result());
+            OwnedNode::try_from_ptr(value).ok_or(LOCKDOWN_E_MISSING_VALUE)
+        }
+    }
+
+    /// Reads a value from lockdownd and converts it to a Rust type. A value which cannot be
+    /// converted is reported as `Error::Plist`.
+    ///
+    /// ```rust,no_run
+    /// use libimobiledevice::{Device, LockdownClient};
+    ///
+    /// let device = Device::any().unwrap();
+    /// let client = LockdownClient::new_with_handshake(&device, None).unwrap();
+    /// let version = client.get_value_as::<String>(None, Some("ProductVersion")).unwrap();
+    /// ```
+    pub fn get_value_as<T: FromPlistNode>(&self, domain: Option<&str>, key: Option<&str>) -> Result<T, Error> {
+        let node = try!(self.get_value(domain, key));
+        Ok(try!(T::from_plist_node(&node)))
+    }
+
+    /// Writes a value to lockdownd. If `domain` is `None`, writes to the default domain.
+    ///
+    /// Keys which cannot be changed fail with `SetValueError::Prohibited` or
+    /// `SetValueError::Immutable`.
+    pub fn set_value<T: ToPlistNode + ?Sized>(&self, domain: Option<&str>, key: &str, value: &T) -> Result<(), SetValueError> {
+        let domain = try!(to_opt_c_str(domain).map_err(|_| LOCKDOWN_E_INVALID_ARG));
+        let key = try!(key.to_c_str().map_err(|_| LOCKDOWN_E_INVALID_ARG));
+        // lockdownd_set_value takes ownership of the node.
+        let value = value.to_plist_node().take();
+        Ok(try!(unsafe { lockdownd_set_value(self.0, opt_ptr(&domain), key.as_ptr(), value) }.to_result()))
+    }
+
+    /// Removes a value from lockdownd. If `domain` is `None`, removes from the default domain.
+    ///
+    /// Keys which cannot be removed are reported as `LOCKDOWN_E_REMOVE_PROHIBITED`.
+    pub fn remove_value(&self, domain: Option<&str>, key: &str) -> Result<(), lockdownd_error_t> {
+        let domain = try!(to_opt_c_str(domain).map_err(|_| LOCKDOWN_E_INVALID_ARG));
+        let key = try!(key.to_c_str().map_err(|_| LOCKDOWN_E_INVALID_ARG));
+        unsafe { lockdownd_remove_value(self.0, opt_ptr(&domain), key.as_ptr()) }.to_result()
+    }
+
+    /// Requests lockdownd to start a service, e.g. `com.apple.afc`. The returned descriptor can be
+    /// passed to the service client constructors.
+    pub fn start_service(&self, identifier: &str) -> Result<ServiceDescriptor, lockdownd_error_t> {
+        let identifier = try!(identifier.to_c_str().map_err(|_| LOCKDOWN_E_INVALID_ARG));
+        let mut service = null_mut();
+        unsafe {
+            try!(lockdownd_start_service(self.0, identifier.as_ptr(), &mut service).to_result());
+            Ok(ServiceDescriptor::from_ptr(service))
+        }
+    }
+
+    /// Lists the data classes the device can synchronize, e.g. `com.apple.Contacts`.
+    pub fn sync_data_classes(&self) -> Result<Vec<String>, lockdownd_error_t> {
+        let mut classes = null_mut();
+        let mut count = 0;
+        unsafe {
+            try!(lockdownd_get_sync_data_classes(self.0, &mut classes, &mut count).to_result());
+            if classes.is_null() {
+                return Ok(Vec::new());
+            }
+            let result = (0..count as isize).map(|i| from_c_str_lossy(*classes.offset(i))).collect();
+            lockdownd_data_classes_free(classes);
+            Ok(result)
+        }
+    }
+
+    /// Starts a session with lockdownd using the host ID from the pair record. The session is
+    /// stopped when the returned guard is dropped.
+    pub fn start_session(&self, host_id: &str) -> Result<SessionGuard, lockdownd_error_t> {
+        let host_id = try!(host_id.to_c_str().map_err(|_| LOCKDOWN_E_INVALID_ARG));
+        let mut session_id = null_mut();
+        let mut ssl_enabled = 0;
+        unsafe {
+            try!(lockdownd_start_session(self.0, host_id.as_ptr(), &mut session_id, &mut ssl_enabled).to_result());
+            // Keep the exact bytes, since the same ID must be passed back to stop the session.
+            let owned_session_id = if session_id.is_null() {
+                None
+            } else {
+                let copy = CStr::from_ptr(session_id).to_owned();
+                free(session_id as *mut c_void);
+                Some(copy)
+            };
+            Ok(SessionGuard {
+                client: self,
+                session_id: owned_session_id,
+                ssl_enabled: ssl_enabled != 0,
+            })
+        }
+    }
+}
+
+/// Error returned by `LockdownClient::set_value`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SetValueError {
+    /// lockdownd does not allow changing this key (`LOCKDOWN_E_SET_PROHIBITED`).
+    Prohibited,
+    /// The value is read-only (`LOCKDOWN_E_IMMUTABLE_VALUE`).
+    Immutable,
+    /// Any other lockdownd error.
+    Other(lockdownd_error_t),
+}
+
+impl From<lockdownd_error_t> for SetValueError {
+    fn from(e: lockdownd_error_t) -> SetValueError {
+        match e {
+            LOCKDOWN_E_SET_PROHIBITED => SetValueError::Prohibited,
+            LOCKDOWN_E_IMMUTABLE_VALUE => SetValueError::Immutable,
+            e => SetValueError::Other(e),
+        }
+    }
+}
+
+impl From<SetValueError> for lockdownd_error_t {
+    fn from(e: SetValueError) -> lockdownd_error_t {
+        match e {
+            SetValueError::Prohibited => LOCKDOWN_E_SET_PROHIBITED,
+            SetValueError::Immutable => LOCKDOWN_E_IMMUTABLE_VALUE,
+            SetValueError::Other(e) => e,
+        }
+    }
+}
+
+impl From<SetValueError> for Error {
+    fn from(e: SetValueError) -> Error {
+        Error::Lockdown(e.into())
+    }
+}
+
+/// An active lockdownd session. The session is stopped when dropped.
+pub struct SessionGuard<'a> {
+    client: &'a LockdownClient,
+    /// `None` if lockdownd did not report an ID, in which case there is nothing to stop.
+    session_id: Option<CString>,
+    ssl_enabled: bool,
+}
+
+impl<'a> SessionGuard<'a> {
+    /// The session ID assigned by lockdownd, or `None` if it did not report one.
+    pub fn session_id(&self) -> Option<Cow<str>> {
+        self.session_id.as_ref().map(|id| id.to_string_lossy())
+    }
+
+    /// Whether the lockdownd connection has been switched to SSL for this session.
+    pub fn ssl_enabled(&self) -> bool {
+        self.ssl_enabled
+    }
+}
+
+impl<'a> fmt::Debug for SessionGuard<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("SessionGuard")
+            .field("session_id", &self.session_id())
+            .field("ssl_enabled", &self.ssl_enabled)
+            .finish()
+    }
+}
+
+impl<'a> Drop for SessionGuard<'a> {
+    fn drop(&mut self) {
+        if let Some(ref session_id) = self.session_id {
+            unsafe { lockdownd_stop_session(self.client.0, session_id.as_ptr()) };
+        }
+    }
+}
+
+/// Describes how to connect to a service started by lockdownd. The descriptor is freed when
+/// dropped.
+pub struct ServiceDescriptor(lockdownd_service_descriptor_t);
+
+unsafe impl Send for ServiceDescriptor {}
+
+impl ServiceDescriptor {
+    pub unsafe fn from_ptr(service: lockdownd_service_descriptor_t) -> ServiceDescriptor {
+        ServiceDescriptor(service)
+    }
+
+    pub fn as_ptr(&self) -> lockdownd_service_descriptor_t {
+        self.0
+    }
+
+    /// The port on the device the service is listening on.
+    pub fn port(&self) -> u16 {
+        unsafe { (*self.0).port }
+    }
+
+    /// Whether the connection to the service must be SSL-encrypted.
+    pub fn ssl_enabled(&self) -> bool {
+        unsafe { (*self.0).ssl_enabled != 0 }
+    }
+}
+
+impl Drop for ServiceDescriptor {
+    fn drop(&mut self) {
+        unsafe { lockdownd_service_descriptor_free(self.0) };
+    }
+}
+
+impl fmt::Debug for ServiceDescriptor {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("ServiceDescriptor")
+            .field("port", &self.port())
+            .field("ssl_enabled", &self.ssl_enabled())
+            .finish()
+    }
+}
+
+impl Drop for LockdownClient {
+    fn drop(&mut self) {
+        unsafe { lockdownd_client_free(self.0) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SetValueError;
+    use libimobiledevice_sys::lockdown::*;
+
+    #[test]
+    fn test_set_value_error() {
+        assert_eq!(SetValueError::from(LOCKDOWN_E_SET_PROHIBITED), SetValueError::Prohibited);
+        assert_eq!(SetValueError::from(LOCKDOWN_E_IMMUTABLE_VALUE), SetValueError::Immutable);
+        assert_eq!(SetValueError::from(LOCKDOWN_E_INVALID_ARG), SetValueError::Other(LOCKDOWN_E_INVALID_ARG));
+        for &code in &[LOCKDOWN_E_SET_PROHIBITED, LOCKDOWN_E_IMMUTABLE_VALUE, LOCKDOWN_E_INVALID_ARG] {
+            assert_eq!(lockdownd_error_t::from(SetValueError::from(code)), code);
+        }
+    }
+}
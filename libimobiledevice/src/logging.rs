@@ -0,0 +1,58 @@
+//! Integration with the `log` crate.
+//!
+//! libimobiledevice and libusbmuxd print their diagnostics to stderr when their debug level is
+//! raised. The functions here derive those levels from a `log::LevelFilter`, so the native output
+//! follows the verbosity configured for the Rust application. The output itself still goes to
+//! stderr.
+
+use libimobiledevice_sys::idevice_set_debug_level;
+use libusbmuxd_sys::libusbmuxd_set_debug_level;
+use libc::c_int;
+use log::{self, LevelFilter};
+
+/// The libimobiledevice debug level for a filter. The library only has "off" and "on", and its
+/// output is very verbose, so it is enabled for `Debug` and `Trace` only.
+fn idevice_level(filter: LevelFilter) -> c_int {
+    if filter >= LevelFilter::Debug { 1 } else { 0 }
+}
+
+/// The libusbmuxd debug level for a filter: 1 prints errors, 2 informational messages, 3 and
+/// above everything.
+fn usbmuxd_level(filter: LevelFilter) -> c_int {
+    match filter {
+        LevelFilter::Off => 0,
+        LevelFilter::Error | LevelFilter::Warn => 1,
+        LevelFilter::Info => 2,
+        LevelFilter::Debug | LevelFilter::Trace => 3,
+    }
+}
+
+/// Sets the debug levels of the native libraries from a level filter.
+pub fn set_debug_level(filter: LevelFilter) {
+    unsafe {
+        idevice_set_debug_level(idevice_level(filter));
+        libusbmuxd_set_debug_level(usbmuxd_level(filter));
+    }
+}
+
+/// Sets the debug levels of the native libraries from `log::max_level()`. Call this after
+/// installing the logger.
+pub fn init() {
+    set_debug_level(log::max_level());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{idevice_level, usbmuxd_level};
+    use log::LevelFilter;
+
+    #[test]
+    fn test_levels() {
+        assert_eq!(idevice_level(LevelFilter::Off), 0);
+        assert_eq!(idevice_level(LevelFilter::Info), 0);
+        assert_eq!(idevice_level(LevelFilter::Trace), 1);
+        assert_eq!(usbmuxd_level(LevelFilter::Off), 0);
+        assert_eq!(usbmuxd_level(LevelFilter::Warn), 1);
+        assert_eq!(usbmuxd_level(LevelFilter::Debug), 3);
+    }
+}
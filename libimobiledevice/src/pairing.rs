@@ -0,0 +1,220 @@
+//! Pairing the host with a device.
+
+use libimobiledevice_sys::lockdown::*;
+use libplist::c_str::from_c_str_lossy;
+use libc::c_char;
+
+use std::cmp::min;
+use std::ffi::CString;
+use std::ptr::null_mut;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use lockdown::LockdownClient;
+use internal::ToResult;
+
+/// The certificates and identifiers exchanged when pairing with a device.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PairRecord {
+    /// The device certificate in PEM format.
+    pub device_certificate: String,
+    /// The host certificate in PEM format.
+    pub host_certificate: String,
+    /// The root certificate in PEM format.
+    pub root_certificate: String,
+    /// The host ID, an uppercase UUID.
+    pub host_id: String,
+    /// The system BUID of usbmuxd.
+    pub system_buid: String,
+}
+
+/// A `lockdownd_pair_record` borrowing the strings it points to.
+struct RawPairRecord {
+    _strings: Vec<Option<CString>>,
+    raw: lockdownd_pair_record,
+}
+
+impl PairRecord {
+    /// Copies the strings out of a C pair record. Null fields become empty strings.
+    pub unsafe fn from_raw(raw: &lockdownd_pair_record) -> PairRecord {
+        let copy = |s: *mut c_char| if s.is_null() { String::new() } else { from_c_str_lossy(s) };
+        PairRecord {
+            device_certificate: copy(raw.device_certificate),
+            host_certificate: copy(raw.host_certificate),
+            root_certificate: copy(raw.root_certificate),
+            host_id: copy(raw.host_id),
+            system_buid: copy(raw.system_buid),
+        }
+    }
+
+    /// Converts to a C pair record. Empty fields become NULL, which libimobiledevice treats as
+    /// absent, the reverse of `from_raw`.
+    fn to_raw(&self) -> Result<RawPairRecord, lockdownd_error_t> {
+        let mut strings = Vec::with_capacity(5);
+        for s in &[&self.device_certificate, &self.host_certificate, &self.root_certificate, &self.host_id, &self.system_buid] {
+            strings.push(if s.is_empty() {
+                None
+            } else {
+                Some(try!(CString::new(s.as_bytes()).map_err(|_| LOCKDOWN_E_INVALID_ARG)))
+            });
+        }
+        // The C functions only read the strings, despite the `*mut` type.
+        let ptr = |i: usize| strings[i].as_ref().map_or(null_mut(), |s| s.as_ptr() as *mut c_char);
+        let raw = lockdownd_pair_record {
+            device_certificate: ptr(0),
+            host_certificate: ptr(1),
+            root_certificate: ptr(2),
+            host_id: ptr(3),
+            system_buid: ptr(4),
+        };
+        Ok(RawPairRecord {
+            _strings: strings,
+            raw: raw,
+        })
+    }
+}
+
+/// Calls a pairing function with the record, or NULL (meaning "use the record stored by usbmuxd,
+/// or generate one") if `record` is `None`.
+fn with_pair_record<F>(record: Option<&PairRecord>, f: F) -> Result<(), lockdownd_error_t>
+    where F: FnOnce(lockdownd_pair_record_t) -> lockdownd_error_t
+{
+    match record {
+        Some(record) => {
+            let mut raw = try!(record.to_raw());
+            f(&mut raw.raw).to_result()
+        }
+        None => f(null_mut()).to_result(),
+    }
+}
+
+impl LockdownClient {
+    /// Pairs the host with the device. If `record` is `None`, a new pair record is generated and
+    /// saved by usbmuxd.
+    ///
+    /// A device with a passcode which has not yet trusted this host fails with
+    /// `LOCKDOWN_E_PASSWORD_PROTECTED` or `LOCKDOWN_E_PAIRING_DIALOG_RESPONSE_PENDING`.
+    pub fn pair(&self, record: Option<&PairRecord>) -> Result<(), lockdownd_error_t> {
+        with_pair_record(record, |r| unsafe { lockdownd_pair(self.as_ptr(), r) })
+    }
+
+    /// Validates the pairing of the host with the device. If `record` is `None`, the pair record
+    /// stored by usbmuxd is used.
+    pub fn validate_pair(&self, record: Option<&PairRecord>) -> Result<(), lockdownd_error_t> {
+        with_pair_record(record, |r| unsafe { lockdownd_validate_pair(self.as_ptr(), r) })
+    }
+
+    /// Unpairs the host from the device. If `record` is `None`, the pair record stored by usbmuxd
+    /// is used.
+    pub fn unpair(&self, record: Option<&PairRecord>) -> Result<(), lockdownd_error_t> {
+        with_pair_record(record, |r| unsafe { lockdownd_unpair(self.as_ptr(), r) })
+    }
+}
+
+/// A state of `pair_interactive` which needs the user's attention.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PairingPrompt {
+    /// The "Trust This Computer?" dialog is shown on the device.
+    DialogPending,
+    /// The device is locked with a passcode and must be unlocked first.
+    PasswordProtected,
+    /// The user tapped "Don't Trust". The dialog appears again when the pairing is retried.
+    UserDenied,
+}
+
+const INITIAL_DELAY_MS: u64 = 500;
+const MAX_DELAY_MS: u64 = 4000;
+
+/// The delay before the given retry (counting from 0), doubling up to a maximum.
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_millis(min(INITIAL_DELAY_MS << min(attempt, 16), MAX_DELAY_MS))
+}
+
+/// Pairs with the device, waiting for the user to accept the trust dialog.
+///
+/// Whenever pairing cannot complete without the user, `prompt` is called with the reason, and
+/// should return `true` to keep waiting or `false` to give up. Pairing is retried with an
+/// increasing delay until it succeeds, `prompt` gives up, or `timeout` elapses; in the latter two
+/// cases the last error is returned. Other errors are returned immediately.
+///
+/// ```rust,no_run
+/// use libimobiledevice::{Device, LockdownClient, PairingPrompt, pair_interactive};
+/// use std::time::Duration;
+///
+/// let device = Device::any().unwrap();
+/// let client = LockdownClient::new(&device, None).unwrap();
+/// pair_interactive(&client, Duration::from_secs(60), |prompt| {
+///     match prompt {
+///         PairingPrompt::DialogPending => println!("Please tap \"Trust\" on the device."),
+///         PairingPrompt::PasswordProtected => println!("Please unlock the device."),
+///         PairingPrompt::UserDenied => return false,
+///     }
+///     true
+/// }).unwrap();
+/// ```
+pub fn pair_interactive<F>(client: &LockdownClient, timeout: Duration, mut prompt: F) -> Result<(), lockdownd_error_t>
+    where F: FnMut(PairingPrompt) -> bool
+{
+    let deadline = Instant::now() + timeout;
+    let mut attempt = 0;
+    loop {
+        let error = match client.pair(None) {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+        let reason = match error {
+            LOCKDOWN_E_PAIRING_DIALOG_RESPONSE_PENDING => PairingPrompt::DialogPending,
+            LOCKDOWN_E_PASSWORD_PROTECTED => PairingPrompt::PasswordProtected,
+            LOCKDOWN_E_USER_DENIED_PAIRING => PairingPrompt::UserDenied,
+            _ => return Err(error),
+        };
+        if !prompt(reason) {
+            return Err(error);
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(error);
+        }
+        sleep(min(backoff(attempt), deadline - now));
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PairRecord, backoff};
+    use std::time::Duration;
+    use libimobiledevice_sys::lockdown::LOCKDOWN_E_INVALID_ARG;
+
+    #[test]
+    fn test_raw_roundtrip() {
+        let record = PairRecord {
+            device_certificate: "-----BEGIN CERTIFICATE-----\nA\n".to_owned(),
+            host_certificate: "-----BEGIN CERTIFICATE-----\nB\n".to_owned(),
+            root_certificate: "-----BEGIN CERTIFICATE-----\nC\n".to_owned(),
+            host_id: "01234567-89AB-CDEF-0123-456789ABCDEF".to_owned(),
+            system_buid: String::new(),
+        };
+        let raw = record.to_raw().unwrap();
+        assert!(!raw.raw.host_id.is_null());
+        assert!(raw.raw.system_buid.is_null());
+        assert_eq!(unsafe { PairRecord::from_raw(&raw.raw) }, record);
+    }
+
+    #[test]
+    fn test_interior_nul() {
+        let record = PairRecord {
+            host_id: "a\0b".to_owned(),
+            ..PairRecord::default()
+        };
+        assert_eq!(record.to_raw().err(), Some(LOCKDOWN_E_INVALID_ARG));
+    }
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(0), Duration::from_millis(500));
+        assert_eq!(backoff(1), Duration::from_millis(1000));
+        assert_eq!(backoff(3), Duration::from_millis(4000));
+        assert_eq!(backoff(100), Duration::from_millis(4000));
+    }
+}
@@ -0,0 +1,291 @@
+//! Client of the syslog relay service, which streams the system log of a device.
+
+use libimobiledevice_sys::syslog_relay::*;
+use libc::c_char;
+
+use std::fmt;
+use std::ptr::null_mut;
+use std::time::Duration;
+
+use device::Device;
+use lockdown::ServiceDescriptor;
+use internal::{ToResult, clamp_len, to_label, to_millis};
+
+/// An owned syslog relay client. The client is freed when dropped.
+pub struct SyslogRelayClient(syslog_relay_client_t);
+
+unsafe impl Send for SyslogRelayClient {}
+
+impl SyslogRelayClient {
+    pub unsafe fn from_ptr(client: syslog_relay_client_t) -> SyslogRelayClient {
+        SyslogRelayClient(client)
+    }
+
+    pub fn as_ptr(&self) -> syslog_relay_client_t {
+        self.0
+    }
+
+    /// Connects to a syslog relay service started by `LockdownClient::start_service`.
+    pub fn new(device: &Device, service: &ServiceDescriptor) -> Result<SyslogRelayClient, syslog_relay_error_t> {
+        let mut client = null_mut();
+        unsafe {
+            try!(syslog_relay_client_new(device.as_ptr(), service.as_ptr(), &mut client).to_result());
+            Ok(SyslogRelayClient::from_ptr(client))
+        }
+    }
+
+    /// Starts the syslog relay service through lockdownd and connects to it. If `label` is
+    /// `None`, the name of the current executable is used.
+    pub fn start_service(device: &Device, label: Option<&str>) -> Result<SyslogRelayClient, syslog_relay_error_t> {
+        let label = try!(to_label(label).map_err(|_| SYSLOG_RELAY_E_INVALID_ARG));
+        let mut client = null_mut();
+        unsafe {
+            try!(syslog_relay_client_start_service(device.as_ptr(), &mut client, label.as_ptr()).to_result());
+            Ok(SyslogRelayClient::from_ptr(client))
+        }
+    }
+
+    /// Receives raw log data into `buf`, waiting at most `timeout`. Returns the number of bytes
+    /// received, which may be 0 if nothing arrived in time.
+    pub fn receive(&self, buf: &mut [u8], timeout: Duration) -> Result<usize, syslog_relay_error_t> {
+        let mut received = 0;
+        let result = unsafe {
+            syslog_relay_receive_with_timeout(self.0, buf.as_mut_ptr() as *mut c_char, clamp_len(buf.len()), &mut received, to_millis(timeout))
+        };
+        match result {
+            #[cfg(libimobiledevice_1_3)]
+            SYSLOG_RELAY_E_TIMEOUT => Ok(0),
+            // libimobiledevice 1.2 reports an expired timeout as an unknown error.
+            #[cfg(not(libimobiledevice_1_3))]
+            SYSLOG_RELAY_E_UNKNOWN_ERROR if received == 0 => Ok(0),
+            _ => {
+                try!(result.to_result());
+                Ok(received as usize)
+            }
+        }
+    }
+}
+
+impl Drop for SyslogRelayClient {
+    fn drop(&mut self) {
+        unsafe { syslog_relay_client_free(self.0) };
+    }
+}
+
+impl fmt::Debug for SyslogRelayClient {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_tuple("SyslogRelayClient").field(&self.0).finish()
+    }
+}
+
+/// A line of the system log, e.g.
+/// `Oct 16 12:34:56 iPhone SpringBoard(UIKit)[57] <Notice>: message`.
+///
+/// Lines not in this format, such as the continuation of a multi-line message, are kept whole in
+/// `message` with the other fields empty.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyslogLine {
+    /// The local time of the message, as formatted by the device (`Oct 16 12:34:56`).
+    pub timestamp: String,
+    /// The name of the device.
+    pub device_name: String,
+    /// The name of the process.
+    pub process: String,
+    /// The library which logged the message on behalf of the process, if shown.
+    pub library: Option<String>,
+    /// The process ID.
+    pub pid: Option<u32>,
+    /// The log level, e.g. `Notice` or `Error`, if shown.
+    pub level: Option<String>,
+    /// The message text. Control characters remain escaped as sent by the device.
+    pub message: String,
+}
+
+impl SyslogLine {
+    /// Splits a log line into its fields.
+    pub fn parse(line: &str) -> SyslogLine {
+        parse_fields(line).unwrap_or_else(|| SyslogLine {
+            message: line.to_owned(),
+            ..SyslogLine::default()
+        })
+    }
+}
+
+fn parse_fields(line: &str) -> Option<SyslogLine> {
+    if line.len() <= 16 || !line.is_char_boundary(15) || line.as_bytes()[15] != b' ' {
+        return None;
+    }
+    let rest = &line[16..];
+
+    // The device name may contain spaces, so the process is located by its "[pid]" instead.
+    let open = match rest.find('[') { Some(i) => i, None => return None };
+    let close = match rest[open..].find(']') { Some(i) => open + i, None => return None };
+    let pid = match rest[open + 1..close].parse() { Ok(pid) => pid, Err(_) => return None };
+    let head = &rest[..open];
+    let space = match head.rfind(' ') { Some(i) => i, None => return None };
+    let (process, library) = split_library(&head[space + 1..]);
+
+    let mut tail = &rest[close + 1..];
+    let mut level = None;
+    if tail.starts_with(" <") {
+        let end = match tail.find('>') { Some(i) => i, None => return None };
+        level = Some(tail[2..end].to_owned());
+        tail = &tail[end + 1..];
+    }
+    if !tail.starts_with(':') {
+        return None;
+    }
+    let message = &tail[1..];
+
+    Some(SyslogLine {
+        timestamp: line[..15].to_owned(),
+        device_name: head[..space].to_owned(),
+        process: process.to_owned(),
+        library: library.map(|s| s.to_owned()),
+        pid: Some(pid),
+        level: level,
+        message: if message.starts_with(' ') { &message[1..] } else { message }.to_owned(),
+    })
+}
+
+/// Splits `process(library)` into its parts.
+fn split_library(s: &str) -> (&str, Option<&str>) {
+    if s.ends_with(')') {
+        if let Some(i) = s.find('(') {
+            return (&s[..i], Some(&s[i + 1..s.len() - 1]));
+        }
+    }
+    (s, None)
+}
+
+/// Reassembles the received chunks into lines.
+#[derive(Debug, Default)]
+struct LineBuffer(Vec<u8>);
+
+impl LineBuffer {
+    /// Appends received data. The relay separates messages with NUL bytes, which are dropped.
+    fn push(&mut self, data: &[u8]) {
+        self.0.extend(data.iter().filter(|&&b| b != 0));
+    }
+
+    /// Removes the next complete non-empty line, without the line terminator.
+    fn pop_line(&mut self) -> Option<String> {
+        while let Some(end) = self.0.iter().position(|&b| b == b'\n') {
+            let line = String::from_utf8_lossy(&self.0[..end]).trim_end_matches('\r').to_owned();
+            self.0.drain(..end + 1);
+            if !line.is_empty() {
+                return Some(line);
+            }
+        }
+        None
+    }
+}
+
+const RECEIVE_TIMEOUT_MS: u64 = 1000;
+
+/// An iterator over the lines of the system log of a device. Iteration blocks until a complete
+/// line arrives, and stops after the first error.
+///
+/// ```rust,no_run
+/// use libimobiledevice::{Device, SyslogRelayClient, SyslogStream};
+///
+/// let device = Device::any().unwrap();
+/// let client = SyslogRelayClient::start_service(&device, None).unwrap();
+/// for line in SyslogStream::new(client) {
+///     let line = line.unwrap();
+///     println!("{} [{:?}] {}", line.process, line.pid, line.message);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SyslogStream {
+    client: SyslogRelayClient,
+    buffer: LineBuffer,
+    finished: bool,
+}
+
+impl SyslogStream {
+    pub fn new(client: SyslogRelayClient) -> SyslogStream {
+        SyslogStream {
+            client: client,
+            buffer: LineBuffer::default(),
+            finished: false,
+        }
+    }
+
+    /// Returns the client, dropping any partially received line.
+    pub fn into_inner(self) -> SyslogRelayClient {
+        self.client
+    }
+}
+
+impl Iterator for SyslogStream {
+    type Item = Result<SyslogLine, syslog_relay_error_t>;
+
+    fn next(&mut self) -> Option<Result<SyslogLine, syslog_relay_error_t>> {
+        let mut chunk = [0; 4096];
+        loop {
+            if let Some(line) = self.buffer.pop_line() {
+                return Some(Ok(SyslogLine::parse(&line)));
+            }
+            if self.finished {
+                return None;
+            }
+            match self.client.receive(&mut chunk, Duration::from_millis(RECEIVE_TIMEOUT_MS)) {
+                Ok(n) => self.buffer.push(&chunk[..n]),
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SyslogLine, LineBuffer};
+
+    #[test]
+    fn test_parse() {
+        let line = SyslogLine::parse("Oct 16 12:34:56 Kenny's iPhone SpringBoard(UIKit)[57] <Notice>: Hello: world");
+        assert_eq!(line, SyslogLine {
+            timestamp: "Oct 16 12:34:56".to_owned(),
+            device_name: "Kenny's iPhone".to_owned(),
+            process: "SpringBoard".to_owned(),
+            library: Some("UIKit".to_owned()),
+            pid: Some(57),
+            level: Some("Notice".to_owned()),
+            message: "Hello: world".to_owned(),
+        });
+
+        let line = SyslogLine::parse("Oct  6 01:02:03 iPad kernel[0]: message");
+        assert_eq!(line.device_name, "iPad");
+        assert_eq!(line.process, "kernel");
+        assert_eq!(line.library, None);
+        assert_eq!(line.pid, Some(0));
+        assert_eq!(line.level, None);
+        assert_eq!(line.message, "message");
+    }
+
+    #[test]
+    fn test_parse_unstructured() {
+        let line = SyslogLine::parse("\tcontinued [not a pid]");
+        assert_eq!(line, SyslogLine {
+            message: "\tcontinued [not a pid]".to_owned(),
+            ..SyslogLine::default()
+        });
+    }
+
+    #[test]
+    fn test_line_buffer() {
+        let mut buffer = LineBuffer::default();
+        buffer.push(b"first li");
+        assert_eq!(buffer.pop_line(), None);
+        buffer.push(b"ne\n\0\nsecond\r\nthi");
+        assert_eq!(buffer.pop_line(), Some("first line".to_owned()));
+        assert_eq!(buffer.pop_line(), Some("second".to_owned()));
+        assert_eq!(buffer.pop_line(), None);
+        buffer.push(b"rd\n");
+        assert_eq!(buffer.pop_line(), Some("third".to_owned()));
+    }
+}
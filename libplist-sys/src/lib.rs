@@ -10,7 +10,8 @@
 
 #![allow(non_camel_case_types)]
 
-use std::os::raw::{c_void, c_char, c_double};
+use std::fmt;
+use std::os::raw::{c_void, c_char, c_double, c_int};
 
 #[repr(C)]
 #[doc(hidden)]
@@ -22,41 +23,85 @@ pub type plist_t = *mut plist_private;
 pub struct plist_dict_iter_private(c_void);
 pub type plist_dict_iter = *mut plist_dict_iter_private;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-#[repr(u32)]
-pub enum plist_type {
+/// The type of a node. Kept as a plain integer since newer libplist versions add and renumber
+/// types, e.g. `PLIST_NULL` in libplist 2.3.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[repr(transparent)]
+pub struct plist_type(pub c_int);
+
+#[allow(non_upper_case_globals)]
+impl plist_type {
     /// The node is a boolean.
-    Boolean,
+    pub const Boolean: plist_type = plist_type(0);
 
     /// The node is an integer (u64).
-    UInt,
+    pub const UInt: plist_type = plist_type(1);
 
     /// The node is a real number (f64).
-    Real,
+    pub const Real: plist_type = plist_type(2);
 
     /// The node is a UTF-8 string.
-    String,
+    pub const String: plist_type = plist_type(3);
 
     /// The node is an array.
-    Array,
+    pub const Array: plist_type = plist_type(4);
 
     /// The node is a dictionary (map).
-    Dict,
+    pub const Dict: plist_type = plist_type(5);
 
     /// The node is a date/timestamp.
-    Date,
+    pub const Date: plist_type = plist_type(6);
 
     /// The node is raw binary data.
-    Data,
+    pub const Data: plist_type = plist_type(7);
 
     /// The node is a dictionary key.
-    Key,
+    pub const Key: plist_type = plist_type(8);
 
     /// The node is a unique ID used in archived object graph.
-    Uid,
+    pub const Uid: plist_type = plist_type(9);
 
     /// No type.
-    None,
+    pub const None: plist_type = plist_type(10);
+
+    /// The name of this type, or `None` if the type is unknown to these bindings.
+    pub fn name(self) -> Option<&'static str> {
+        Some(match self {
+            PLIST_BOOLEAN => "Boolean",
+            PLIST_UINT => "UInt",
+            PLIST_REAL => "Real",
+            PLIST_STRING => "String",
+            PLIST_ARRAY => "Array",
+            PLIST_DICT => "Dict",
+            PLIST_DATE => "Date",
+            PLIST_DATA => "Data",
+            PLIST_KEY => "Key",
+            PLIST_UID => "Uid",
+            PLIST_NONE => "None",
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Debug for plist_type {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.name() {
+            Some(name) => formatter.write_str(name),
+            None => write!(formatter, "Unknown({})", self.0),
+        }
+    }
+}
+
+impl From<c_int> for plist_type {
+    fn from(ty: c_int) -> plist_type {
+        plist_type(ty)
+    }
+}
+
+impl From<plist_type> for c_int {
+    fn from(ty: plist_type) -> c_int {
+        ty.0
+    }
 }
 
 pub const PLIST_BOOLEAN: plist_type = plist_type::Boolean;
@@ -178,3 +223,9 @@ fn test_validity() {
 }
 
 
+
+#[test]
+fn test_unknown_type() {
+    assert_eq!(format!("{:?}", PLIST_DICT), "Dict");
+    assert_eq!(format!("{:?}", plist_type(-1)), "Unknown(-1)");
+}
@@ -0,0 +1,78 @@
+#[macro_use] extern crate criterion;
+extern crate libplist;
+
+use criterion::{Criterion, BenchmarkId, Throughput};
+use libplist::{OwnedNode, FromPlistNode, ToPlistNode};
+
+use std::collections::HashMap;
+
+const SIZES: &'static [usize] = &[16, 1024, 65536];
+
+fn make_dict(size: usize) -> HashMap<String, u64> {
+    (0..size).map(|i| (format!("key{}", i), i as u64)).collect()
+}
+
+fn make_array(size: usize) -> Vec<String> {
+    (0..size).map(|i| format!("value{}", i)).collect()
+}
+
+fn bench_to_node(c: &mut Criterion) {
+    let mut group = c.benchmark_group("to_plist_node");
+    for &size in SIZES {
+        let dict = make_dict(size);
+        let array = make_array(size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("dict", size), &dict, |b, d| b.iter(|| d.to_plist_node()));
+        group.bench_with_input(BenchmarkId::new("array", size), &array, |b, a| b.iter(|| a.to_plist_node()));
+    }
+    group.finish();
+}
+
+fn bench_from_node(c: &mut Criterion) {
+    let mut group = c.benchmark_group("from_plist_node");
+    for &size in SIZES {
+        let dict = make_dict(size).to_plist_node();
+        let array = make_array(size).to_plist_node();
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("dict", size), &dict, |b, n| {
+            b.iter(|| HashMap::<String, u64>::from_plist_node(n).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("array", size), &array, |b, n| {
+            b.iter(|| Vec::<String>::from_plist_node(n).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_serialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialize");
+    for &size in SIZES {
+        let node = make_dict(size).to_plist_node();
+        group.throughput(Throughput::Bytes(node.to_xml().len() as u64));
+        group.bench_with_input(BenchmarkId::new("xml", size), &node, |b, n| b.iter(|| n.to_xml()));
+        group.throughput(Throughput::Bytes(node.to_binary().len() as u64));
+        group.bench_with_input(BenchmarkId::new("binary", size), &node, |b, n| b.iter(|| n.to_binary()));
+    }
+    group.finish();
+}
+
+fn bench_deserialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deserialize");
+    for &size in SIZES {
+        let node = make_dict(size).to_plist_node();
+        let xml = node.to_xml().to_string();
+        let binary = node.to_binary().to_vec();
+        group.throughput(Throughput::Bytes(xml.len() as u64));
+        group.bench_with_input(BenchmarkId::new("xml", size), &xml, |b, x| {
+            b.iter(|| OwnedNode::from_xml(x).unwrap())
+        });
+        group.throughput(Throughput::Bytes(binary.len() as u64));
+        group.bench_with_input(BenchmarkId::new("binary", size), &binary, |b, x| {
+            b.iter(|| OwnedNode::from_binary(x).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_to_node, bench_from_node, bench_serialize, bench_deserialize);
+criterion_main!(benches);
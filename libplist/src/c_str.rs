@@ -1,28 +1,102 @@
+//! Conversion of strings crossing the FFI boundary.
+//!
+//! Strings passed to or read from the C libraries follow one policy:
+//!
+//! * **Strict** conversions are the default. Rust strings with an interior NUL are rejected with
+//!   `NulError` (`to_c_str`), and C strings which are not valid UTF-8 are rejected with
+//!   `Utf8Error` (`from_c_str`).
+//! * **Lossy** conversions are opt-in and never fail. Rust strings are truncated at the first NUL
+//!   (`to_c_str_lossy`), and invalid UTF-8 sequences in C strings are replaced by U+FFFD
+//!   (`from_c_str_lossy`).
+
 use std::ffi::{CStr, CString, NulError};
 use std::borrow::Cow;
 use std::ops::Deref;
+use std::str::Utf8Error;
+
+use libc::c_char;
 
 /// A convenient trait to return a `Cow<CStr>` from a string-like object. This
 /// method may cause allocation.
 pub trait ToCStr {
+    /// Converts to a C string, failing if the string contains an interior NUL.
     fn to_c_str(&self) -> Result<Cow<CStr>, NulError>;
+
+    /// Converts to a C string, truncating the string at the first interior NUL.
+    fn to_c_str_lossy(&self) -> Cow<CStr>;
 }
 
 impl ToCStr for CStr {
     fn to_c_str(&self) -> Result<Cow<CStr>, NulError> {
         Ok(Cow::Borrowed(self))
     }
+
+    fn to_c_str_lossy(&self) -> Cow<CStr> {
+        Cow::Borrowed(self)
+    }
 }
 
 impl ToCStr for str {
     fn to_c_str(&self) -> Result<Cow<CStr>, NulError> {
         Ok(Cow::Owned(try!(CString::new(self))))
     }
+
+    fn to_c_str_lossy(&self) -> Cow<CStr> {
+        let truncated = match self.find('\0') {
+            Some(pos) => &self[..pos],
+            None => self,
+        };
+        Cow::Owned(CString::new(truncated).unwrap())
+    }
 }
 
 impl<R: Deref> ToCStr for R where R::Target: ToCStr {
     fn to_c_str(&self) -> Result<Cow<CStr>, NulError> {
         self.deref().to_c_str()
     }
+
+    fn to_c_str_lossy(&self) -> Cow<CStr> {
+        self.deref().to_c_str_lossy()
+    }
+}
+
+/// Copies a borrowed C string into a `String`, failing if it is not valid UTF-8.
+pub unsafe fn from_c_str(ptr: *const c_char) -> Result<String, Utf8Error> {
+    CStr::from_ptr(ptr).to_str().map(|s| s.to_owned())
+}
+
+/// Copies a borrowed C string into a `String`, replacing invalid UTF-8 sequences with U+FFFD.
+pub unsafe fn from_c_str_lossy(ptr: *const c_char) -> String {
+    CStr::from_ptr(ptr).to_string_lossy().into_owned()
 }
 
+#[cfg(test)]
+mod tests {
+    use super::{ToCStr, from_c_str, from_c_str_lossy};
+    use libc::c_char;
+
+    #[test]
+    fn test_strict() {
+        assert_eq!(&*"abc".to_c_str().unwrap(), const_cstr!("abc").as_cstr());
+        assert!("a\0bc".to_c_str().is_err());
+    }
+
+    #[test]
+    fn test_lossy() {
+        assert_eq!(&*"abc".to_c_str_lossy(), const_cstr!("abc").as_cstr());
+        assert_eq!(&*"a\0bc".to_c_str_lossy(), const_cstr!("a").as_cstr());
+        assert_eq!(&*String::from("\0").to_c_str_lossy(), const_cstr!("").as_cstr());
+    }
+
+    #[test]
+    fn test_from_c_str() {
+        let valid = b"caf\xc3\xa9\0".as_ptr() as *const c_char;
+        let invalid = b"caf\xe9\0".as_ptr() as *const c_char;
+        unsafe {
+            assert_eq!(from_c_str(valid).unwrap(), "caf\u{e9}");
+            assert!(from_c_str(invalid).is_err());
+            assert_eq!(from_c_str_lossy(valid), "caf\u{e9}");
+            assert_eq!(from_c_str_lossy(invalid), "caf\u{fffd}");
+        }
+    }
+}
@@ -12,7 +12,7 @@ use std::hash::{Hash, BuildHasher};
 use std::time::{UNIX_EPOCH, SystemTime, Duration};
 use std::ffi::{CStr, CString};
 
-use libc::{c_double, c_char};
+use libc::{c_double, c_char, c_void, free};
 
 use node::{Node, OwnedNode, BorrowedNode, FromPlistNode, ToPlistNode};
 use error::PlistError;
@@ -93,6 +93,10 @@ impl FromPlistNode for MString {
         let mut result = null_mut();
         unsafe {
             plist_get_string_val(node.as_ptr(), &mut result);
+            if let Err(e) = CStr::from_ptr(result).to_str() {
+                free(result as *mut c_void);
+                return Err(PlistError::Utf8(e));
+            }
             Ok(MString::from_raw_unchecked(result))
         }
     }
@@ -133,6 +137,16 @@ impl ToPlistNode for String {
 
 generate_roundtrip_test!(test_str_roundtrip, "helloworld", String);
 
+#[test]
+fn test_invalid_utf8_string() {
+    let node = OwnedNode::new_str(CStr::from_bytes_with_nul(b"caf\xe9\0").unwrap());
+    match String::from_plist_node(&node) {
+        Err(PlistError::Utf8(_)) => {}
+        r => panic!("unexpected result {:?}", r),
+    }
+    assert_eq!(node.string_lossy().unwrap(), "caf\u{fffd}");
+}
+
 //}}}
 
 //{{{ Array ---------------------------------------------------------------------------------------
@@ -24,7 +24,7 @@ use std::fmt;
 
 use error::PlistError;
 use internal::recv_data;
-use c_str::ToCStr;
+use c_str::{ToCStr, from_c_str_lossy};
 
 //{{{ Node ----------------------------------------------------------------------------------------
 
@@ -56,6 +56,19 @@ impl Node {
         }
     }
 
+    /// Reads the value of a string node, replacing invalid UTF-8 sequences with U+FFFD. Use
+    /// `String::from_plist_node` instead to reject invalid strings.
+    pub fn string_lossy(&self) -> Result<String, PlistError> {
+        try!(self.expect_type(PLIST_STRING));
+        let mut result = null_mut();
+        unsafe {
+            plist_get_string_val(self.as_ptr(), &mut result);
+            let string = from_c_str_lossy(result);
+            free(result as *mut c_void);
+            Ok(string)
+        }
+    }
+
     /// Serializes the output to XML property list.
     pub fn to_xml(&self) -> MBox<str> {
         unsafe {
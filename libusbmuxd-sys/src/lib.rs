@@ -13,15 +13,14 @@ pub struct usbmuxd_device_info_t {
     pub udid: [c_char; 41],
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-#[repr(u32)]
-pub enum usbmuxd_event_type {
-    DeviceAdd = 1,
-    DeviceRemove = 2,
-}
+/// The kind of a device event, as stored in `usbmuxd_event_t::event`. Kept as a plain integer
+/// since newer libraries may add event types.
+pub type usbmuxd_event_type = c_int;
 
-pub const UE_DEVICE_ADD: usbmuxd_event_type = usbmuxd_event_type::DeviceAdd;
-pub const UE_DEVICE_REMOVE: usbmuxd_event_type = usbmuxd_event_type::DeviceRemove;
+pub const UE_DEVICE_ADD: usbmuxd_event_type = 1;
+pub const UE_DEVICE_REMOVE: usbmuxd_event_type = 2;
+/// Sent by libusbmuxd 2.0 and later once the device has been paired with this host.
+pub const UE_DEVICE_PAIRED: usbmuxd_event_type = 3;
 
 #[repr(C)]
 pub struct usbmuxd_event_t {
@@ -29,6 +28,11 @@ pub struct usbmuxd_event_t {
     pub device: usbmuxd_device_info_t,
 }
 
+/// Callback invoked on device add/remove events.
+///
+/// This is deliberately `extern "C"` rather than `extern "C-unwind"`: the callback runs on the
+/// listener thread created by `usbmuxd_subscribe` with only C frames above it, so there is nothing
+/// an unwind could propagate to. A panic escaping the callback aborts the process instead.
 pub type usbmuxd_event_cb_t = unsafe extern "C" fn(event: *const usbmuxd_event_t, user_data: *mut c_void);
 
 extern "C" {
@@ -1,4 +1,8 @@
 //! Structures of the `usbmuxd` protocol.
+//!
+//! The structures are not laid out to match the wire format. Use the [`Wire`](trait.Wire.html)
+//! trait to encode and decode them, which always produces the little-endian representation used by
+//! usbmuxd regardless of the host byte order.
 
 use std::os::raw::c_char;
 
@@ -7,68 +11,154 @@ pub const USBMUXD_PROTOCOL_VERSION: i32 = 0;
 pub const USBMUXD_SOCKET_PORT: u16 = 27015;
 pub const USBMUXD_SOCKET_FILE: &'static str = "/var/run/usbmuxd";
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-#[repr(u32)]
-pub enum usbmuxd_result {
-    Ok = 0,
-    BadCommand = 1,
-    BadDev = 2,
-    ConnRefused = 3,
-    BadVersion = 6,
-}
+/// The result code of a `MESSAGE_RESULT` reply, as stored in `usbmuxd_result_msg::result`.
+/// Kept as a plain integer since the daemon may send codes not listed here.
+pub type usbmuxd_result = u32;
 
-pub const RESULT_OK: usbmuxd_result = usbmuxd_result::Ok;
-pub const RESULT_BADCOMMAND: usbmuxd_result = usbmuxd_result::BadCommand;
-pub const RESULT_BADDEV: usbmuxd_result = usbmuxd_result::BadDev;
-pub const RESULT_CONNREFUSED: usbmuxd_result = usbmuxd_result::ConnRefused;
-pub const RESULT_BADVERSION: usbmuxd_result = usbmuxd_result::BadVersion;
+pub const RESULT_OK: usbmuxd_result = 0;
+pub const RESULT_BADCOMMAND: usbmuxd_result = 1;
+pub const RESULT_BADDEV: usbmuxd_result = 2;
+pub const RESULT_CONNREFUSED: usbmuxd_result = 3;
+pub const RESULT_BADVERSION: usbmuxd_result = 6;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-#[repr(u32)]
-pub enum usbmuxd_msgtype {
-    Result = 1,
-    Connect = 2,
-    Listen = 3,
-    DeviceAdd = 4,
-    DeviceRemove = 5,
-    Plist = 8,
+/// The message type, as stored in `usbmuxd_header::message`. Kept as a plain integer since the
+/// daemon may send types not listed here.
+pub type usbmuxd_msgtype = u32;
+
+pub const MESSAGE_RESULT: usbmuxd_msgtype = 1;
+pub const MESSAGE_CONNECT: usbmuxd_msgtype = 2;
+pub const MESSAGE_LISTEN: usbmuxd_msgtype = 3;
+pub const MESSAGE_DEVICE_ADD: usbmuxd_msgtype = 4;
+pub const MESSAGE_DEVICE_REMOVE: usbmuxd_msgtype = 5;
+pub const MESSAGE_PLIST: usbmuxd_msgtype = 8;
+
+/// Explicit wire encoding of a protocol structure.
+pub trait Wire: Sized {
+    /// Number of bytes the structure occupies on the wire.
+    const SIZE: usize;
+
+    /// Appends the wire representation to `out`.
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Decodes the structure from the start of `buf`. Returns `None` if `buf` is too short.
+    fn decode(buf: &[u8]) -> Option<Self>;
 }
 
-pub const MESSAGE_RESULT: usbmuxd_msgtype = usbmuxd_msgtype::Result;
-pub const MESSAGE_CONNECT: usbmuxd_msgtype = usbmuxd_msgtype::Connect;
-pub const MESSAGE_LISTEN: usbmuxd_msgtype = usbmuxd_msgtype::Listen;
-pub const MESSAGE_DEVICE_ADD: usbmuxd_msgtype = usbmuxd_msgtype::DeviceAdd;
-pub const MESSAGE_DEVICE_REMOVE: usbmuxd_msgtype = usbmuxd_msgtype::DeviceRemove;
-pub const MESSAGE_PLIST: usbmuxd_msgtype = usbmuxd_msgtype::Plist;
+fn read_u16_le(buf: &[u8]) -> u16 {
+    u16::from_le_bytes([buf[0], buf[1]])
+}
+
+fn read_u32_le(buf: &[u8]) -> u32 {
+    u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]])
+}
 
-#[repr(packed)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct usbmuxd_header {
     pub length: u32,
     pub version: u32,
-    pub message: u32,
+    pub message: usbmuxd_msgtype,
     pub tag: u32,
 }
 
-#[repr(packed)]
+impl Wire for usbmuxd_header {
+    const SIZE: usize = 16;
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.length.to_le_bytes());
+        out.extend_from_slice(&self.version.to_le_bytes());
+        out.extend_from_slice(&self.message.to_le_bytes());
+        out.extend_from_slice(&self.tag.to_le_bytes());
+    }
+
+    fn decode(buf: &[u8]) -> Option<Self> {
+        if buf.len() < Self::SIZE {
+            return None;
+        }
+        Some(usbmuxd_header {
+            length: read_u32_le(&buf[0..]),
+            version: read_u32_le(&buf[4..]),
+            message: read_u32_le(&buf[8..]),
+            tag: read_u32_le(&buf[12..]),
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct usbmuxd_result_msg {
     pub header: usbmuxd_header,
-    pub result: u32,
+    pub result: usbmuxd_result,
 }
 
-#[repr(packed)]
+impl Wire for usbmuxd_result_msg {
+    const SIZE: usize = usbmuxd_header::SIZE + 4;
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.header.encode(out);
+        out.extend_from_slice(&self.result.to_le_bytes());
+    }
+
+    fn decode(buf: &[u8]) -> Option<Self> {
+        if buf.len() < Self::SIZE {
+            return None;
+        }
+        Some(usbmuxd_result_msg {
+            header: usbmuxd_header::decode(buf).unwrap(),
+            result: read_u32_le(&buf[usbmuxd_header::SIZE..]),
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct usbmuxd_connect_request {
     pub header: usbmuxd_header,
     pub device_id: u32,
+    /// The device port in host byte order. It is transmitted in network (big-endian) byte order.
     pub port: u16,
     pub _reserved: u16,
 }
 
-#[repr(packed)]
+impl Wire for usbmuxd_connect_request {
+    const SIZE: usize = usbmuxd_header::SIZE + 8;
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.header.encode(out);
+        out.extend_from_slice(&self.device_id.to_le_bytes());
+        out.extend_from_slice(&self.port.to_be_bytes());
+        out.extend_from_slice(&self._reserved.to_le_bytes());
+    }
+
+    fn decode(buf: &[u8]) -> Option<Self> {
+        if buf.len() < Self::SIZE {
+            return None;
+        }
+        let rest = &buf[usbmuxd_header::SIZE..];
+        Some(usbmuxd_connect_request {
+            header: usbmuxd_header::decode(buf).unwrap(),
+            device_id: read_u32_le(rest),
+            port: u16::from_be_bytes([rest[4], rest[5]]),
+            _reserved: read_u16_le(&rest[6..]),
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct usbmuxd_listen_request {
     pub header: usbmuxd_header,
 }
 
-#[repr(packed)]
+impl Wire for usbmuxd_listen_request {
+    const SIZE: usize = usbmuxd_header::SIZE;
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.header.encode(out);
+    }
+
+    fn decode(buf: &[u8]) -> Option<Self> {
+        usbmuxd_header::decode(buf).map(|header| usbmuxd_listen_request { header: header })
+    }
+}
+
+#[derive(Copy, Clone)]
 pub struct usbmuxd_device_record {
     pub device_id: u32,
     pub product_id: u16,
@@ -77,3 +167,79 @@ pub struct usbmuxd_device_record {
     pub location: u32,
 }
 
+impl Wire for usbmuxd_device_record {
+    const SIZE: usize = 268;
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.device_id.to_le_bytes());
+        out.extend_from_slice(&self.product_id.to_le_bytes());
+        out.extend(self.serial_number.iter().map(|&c| c as u8));
+        out.extend_from_slice(&self._padding.to_le_bytes());
+        out.extend_from_slice(&self.location.to_le_bytes());
+    }
+
+    fn decode(buf: &[u8]) -> Option<Self> {
+        if buf.len() < Self::SIZE {
+            return None;
+        }
+        let mut serial_number = [0; 256];
+        for (dest, &src) in serial_number.iter_mut().zip(&buf[6..262]) {
+            *dest = src as c_char;
+        }
+        Some(usbmuxd_device_record {
+            device_id: read_u32_le(&buf[0..]),
+            product_id: read_u16_le(&buf[4..]),
+            serial_number: serial_number,
+            _padding: read_u16_le(&buf[262..]),
+            location: read_u32_le(&buf[264..]),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connect_request_encoding() {
+        let request = usbmuxd_connect_request {
+            header: usbmuxd_header {
+                length: usbmuxd_connect_request::SIZE as u32,
+                version: 0,
+                message: MESSAGE_CONNECT,
+                tag: 3,
+            },
+            device_id: 0x0102_0304,
+            port: 62078,
+            _reserved: 0,
+        };
+        let mut buf = Vec::new();
+        request.encode(&mut buf);
+        assert_eq!(&buf[..], &b"\x18\0\0\0\0\0\0\0\x02\0\0\0\x03\0\0\0\x04\x03\x02\x01\xf2\x7e\0\0"[..]);
+        assert_eq!(usbmuxd_connect_request::decode(&buf), Some(request));
+        assert_eq!(usbmuxd_connect_request::decode(&buf[1..]), None);
+    }
+
+    #[test]
+    fn test_device_record_roundtrip() {
+        let mut serial_number = [0; 256];
+        serial_number[0] = b'a' as c_char;
+        serial_number[255] = b'z' as c_char;
+        let record = usbmuxd_device_record {
+            device_id: 7,
+            product_id: 0x12a8,
+            serial_number: serial_number,
+            _padding: 0,
+            location: 0x1420_0000,
+        };
+        let mut buf = Vec::new();
+        record.encode(&mut buf);
+        assert_eq!(buf.len(), usbmuxd_device_record::SIZE);
+        assert_eq!(&buf[..6], b"\x07\0\0\0\xa8\x12");
+        let decoded = usbmuxd_device_record::decode(&buf).unwrap();
+        assert_eq!(decoded.device_id, 7);
+        assert_eq!(decoded.product_id, 0x12a8);
+        assert_eq!(&decoded.serial_number[..], &serial_number[..]);
+        assert_eq!(decoded.location, 0x1420_0000);
+    }
+}